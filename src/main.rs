@@ -71,6 +71,9 @@ async fn main() -> std::io::Result<()> {
         output_dir: "output".to_string(),
     });
 
+    // Pick up jobs that were queued or running when the server last stopped
+    services::job_service::JobService::new().resume_jobs(&app_state.output_dir);
+
     println!("Starting server at http://{}:{}", IP_ADDRESS, PORT);
     HttpServer::new(move || {
         App::new()
@@ -82,6 +85,7 @@ async fn main() -> std::io::Result<()> {
             .configure(routes::query::configure)
             .configure(routes::git::configure)
             .configure(routes::analyze::configure) // ADD THIS LINE
+            .configure(routes::jobs::configure)
             .service(profile_page)
             .service(index_page)
             
@@ -40,6 +40,117 @@ pub struct Project {
     #[serde(default = "default_false")]
     pub git_integration_enabled: bool,
     pub git_branch_name: Option<String>,
+    #[serde(default)]
+    pub vector_backend: VectorBackend,
+    #[serde(default)]
+    pub embedding_config: EmbeddingConfig,
+    /// Collection currently used for search. None means the original `project_<name>` collection.
+    #[serde(default)]
+    pub vector_collection: Option<String>,
+    /// Set while embeddings are being rebuilt for a new model/dimension.
+    #[serde(default)]
+    pub pending_embedding_migration: Option<EmbeddingMigration>,
+    #[serde(default)]
+    pub hybrid_search: HybridSearchConfig,
+    /// Let the agent pull relevant messages from the project's other chats into context
+    #[serde(default)]
+    pub chat_memory_enabled: bool,
+    /// Build Rust files' YAML from the parsed skeleton alone, without calling the LLM
+    #[serde(default)]
+    pub rust_skeleton_only: bool,
+}
+
+impl Project {
+    /// Whether `file_path` is embedded through its YAML summary: the project default or a
+    /// per-file override, except that markdown never has a YAML summary.
+    pub fn uses_yaml_summary(&self, file_path: &str) -> bool {
+        let is_markdown = std::path::Path::new(file_path).extension().and_then(|ext| ext.to_str()) == Some("md");
+        !is_markdown && self.file_yaml_override.get(file_path).copied().unwrap_or(self.default_use_yaml)
+    }
+}
+
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+pub const DEFAULT_EMBEDDING_DIMENSIONS: u32 = 1536;
+
+/// Which implementation produces a project's embeddings.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderKind {
+    /// OpenAI embeddings API, keyed by `OPEN_AI_KEY`
+    #[default]
+    OpenAi,
+    /// Any server speaking the OpenAI `/embeddings` protocol at `base_url`
+    OpenAiCompatible,
+    /// Deterministic feature-hashing embedder; offline, no model download
+    LocalHashing,
+}
+
+/// Embedding provider, model and vector size used for a collection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingConfig {
+    #[serde(default)]
+    pub provider: EmbeddingProviderKind,
+    pub model: String,
+    pub dimensions: u32,
+    /// Base URL for `OpenAiCompatible`, e.g. `http://localhost:11434/v1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        EmbeddingConfig {
+            provider: EmbeddingProviderKind::OpenAi,
+            model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            dimensions: DEFAULT_EMBEDDING_DIMENSIONS,
+            base_url: None,
+        }
+    }
+}
+
+/// Reciprocal rank fusion settings used to merge vector, keyword and symbol rankings.
+/// A file scores `weight / (rrf_k + rank)` per source it appears in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HybridSearchConfig {
+    pub rrf_k: f32,
+    pub vector_weight: f32,
+    pub keyword_weight: f32,
+    pub symbol_weight: f32,
+    /// How many candidates each source contributes before fusion
+    pub candidates_per_source: u64,
+}
+
+impl Default for HybridSearchConfig {
+    fn default() -> Self {
+        HybridSearchConfig {
+            rrf_k: 60.0,
+            vector_weight: 1.0,
+            keyword_weight: 1.0,
+            symbol_weight: 1.0,
+            candidates_per_source: 20,
+        }
+    }
+}
+
+/// A collection being filled in the background. Once every file is embedded it
+/// replaces `Project::vector_collection` and `Project::embedding_config`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingMigration {
+    pub config: EmbeddingConfig,
+    pub collection: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Where a project's embeddings are stored.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorBackend {
+    /// Qdrant server at `QDRANT_SERVER_URL`
+    #[default]
+    Qdrant,
+    /// In-process store persisted under `output/<project>/vector_store/`
+    Local,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
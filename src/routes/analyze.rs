@@ -4,8 +4,7 @@ use crate::services::git_service::GitService;
 use crate::render_svelte;
 use serde::Serialize;
 use std::path::{Path, PathBuf};
-use serde_json::Value;
-use crate::services::utils::html_utils::unescape_html;
+use crate::services::search_service::parse_llm_search_analysis;
 use std::collections::HashMap;
 
 use actix_web::{web, get, Responder, HttpResponse};
@@ -59,27 +58,15 @@ fn process_llm_analysis_suggestions(
     let mut actual_relevant_files: Vec<String> = Vec::new();
 
     if let Some(llm_analysis_str) = project_service.query_manager.get_query_data_field(project_dir, query_id, "llm_analysis") {
-        let llm_analysis_unescaped = unescape_html(llm_analysis_str);
-        let llm_analysis_json_str = llm_analysis_unescaped
-            .split("```json")
-            .nth(1)
-            .and_then(|s| s.split("```").next())
-            .map(|s| s.trim().to_string())
-            .unwrap_or_else(|| "".to_string());
-
-        match serde_json::from_str::<Value>(&llm_analysis_json_str) {
-            Ok(json_value) => {
-                if let Some(files_array) = json_value["suggested_files"].as_array() {
-                    for file_val in files_array {
-                        if let Some(file_path_str) = file_val.as_str() {
-                            // Resolve the file_path_str to an absolute path
-                            let absolute_path: PathBuf = project_source_dir.join(file_path_str);
-                            if let Some(abs_path_str) = absolute_path.to_str() {
-                                llm_suggested_files.push(abs_path_str.to_string());
-                            } else {
-                                eprintln!("Warning: Failed to convert absolute path to string for LLM suggested file: {:?}", absolute_path);
-                            }
-                        }
+        match parse_llm_search_analysis(llm_analysis_str) {
+            Ok(analysis) => {
+                for file_path_str in analysis.suggested_files {
+                    // Resolve the file_path_str to an absolute path
+                    let absolute_path: PathBuf = project_source_dir.join(&file_path_str);
+                    if let Some(abs_path_str) = absolute_path.to_str() {
+                        llm_suggested_files.push(abs_path_str.to_string());
+                    } else {
+                        eprintln!("Warning: Failed to convert absolute path to string for LLM suggested file: {:?}", absolute_path);
                     }
                 }
             },
@@ -0,0 +1,11 @@
+// src/routes/jobs/cancel_job.rs
+use crate::services::job_service::JobService;
+use actix_web::{post, web, HttpResponse, Responder};
+
+#[post("/jobs/{id}/cancel")]
+pub async fn cancel_job(id: web::Path<String>) -> impl Responder {
+    match JobService::new().cancel_job(&id) {
+        Ok(job) => HttpResponse::Ok().json(job),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,16 @@
+// src/routes/jobs/get_job.rs
+use crate::services::job_service::JobService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde_json::json;
+
+/// Full job record including its file stages and log.
+#[get("/jobs/{id}")]
+pub async fn get_job(id: web::Path<String>) -> impl Responder {
+    match JobService::new().get_job(&id) {
+        Some(job) => {
+            let progress = job.progress();
+            HttpResponse::Ok().json(json!({ "job": job, "progress": progress }))
+        }
+        None => HttpResponse::NotFound().body(format!("Job {} not found", id)),
+    }
+}
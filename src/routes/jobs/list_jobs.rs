@@ -0,0 +1,34 @@
+// src/routes/jobs/list_jobs.rs
+use crate::services::job_service::JobService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct ListJobsQuery {
+    pub project: Option<String>,
+}
+
+/// Lists jobs newest first, without their per-file lists; use `/jobs/{id}` for those.
+#[get("/jobs")]
+pub async fn list_jobs(query: web::Query<ListJobsQuery>) -> impl Responder {
+    let jobs: Vec<_> = JobService::new()
+        .list_jobs(query.project.as_deref())
+        .into_iter()
+        .map(|job| {
+            json!({
+                "id": job.id,
+                "project": job.project,
+                "kind": job.kind,
+                "status": job.status,
+                "cancel_requested": job.cancel_requested,
+                "queued_at": job.queued_at,
+                "started_at": job.started_at,
+                "finished_at": job.finished_at,
+                "error": job.error,
+                "progress": job.progress(),
+            })
+        })
+        .collect();
+    HttpResponse::Ok().json(jobs)
+}
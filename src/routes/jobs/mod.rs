@@ -0,0 +1,11 @@
+pub mod list_jobs;
+pub mod get_job;
+pub mod cancel_job;
+
+use actix_web::web;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_jobs::list_jobs)
+        .service(get_job::get_job)
+        .service(cancel_job::cancel_job);
+}
@@ -8,6 +8,7 @@ use git2::Repository;
 use actix_web::web;
 use std::path::Path;
 use crate::services::agent::handle_agentic_message;
+use crate::services::notes_service::{NotesService, ProjectNote};
 // No need to add use uuid::Uuid; here
 
 pub fn get_context_and_contents(project: &Project, app_state: &web::Data<AppState>, query_id: &str) -> (Vec<String>, String) {
@@ -39,6 +40,7 @@ pub fn create_system_prompt(
     file_contents: &str,
     project: &Project, // Project now contains the potentially augmented file_descriptions
     include_file_descriptions: bool,
+    notes: &[ProjectNote], // Project notes relevant to the current message
 ) -> String {
     let mut prompt = format!("You are an AI assistant helping with code analysis for a project. In this chat the user controls which files you see and which messages you see with every prompt. \
         The user's original query was: \"{}\"", query);
@@ -57,6 +59,17 @@ pub fn create_system_prompt(
         // prompt.push_str(&format!("\n\nYou have access to the following files:\n{}", context_files.join("\n")));
     }
 
+    if !notes.is_empty() {
+        prompt.push_str("\n\nProject notes recorded by the team (conventions and facts about this project; follow them):");
+        for note in notes {
+            prompt.push_str(&format!("\n\n### {}", note.title));
+            if !note.tags.is_empty() {
+                prompt.push_str(&format!(" (tags: {})", note.tags.join(", ")));
+            }
+            prompt.push_str(&format!("\n{}", note.body.trim()));
+        }
+    }
+
     if !file_contents.is_empty() {
         prompt.push_str(&format!("\n\nHere are the files and their contents:\n\n{}", file_contents));
     }
@@ -250,7 +263,14 @@ pub async fn handle_chat_message(
         let query_text = project_service.query_manager
             .get_query_data_field(&project_dir, &query_id, "query")
             .unwrap_or_else(|| "No previous query found".to_string());
-        let system_prompt = create_system_prompt(&query_text, &context_files, &file_contents, &project, include_file_descriptions);
+        let notes = NotesService::new()
+            .relevant_notes(project, &project_dir, user_message_content_raw)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to retrieve project notes: {}", e);
+                Vec::new()
+            });
+        let system_prompt = create_system_prompt(&query_text, &context_files, &file_contents, &project, include_file_descriptions, &notes);
 
         // Create the current user message (the one the LLM is responding to)
         let current_user_message_for_llm = ChatMessage {
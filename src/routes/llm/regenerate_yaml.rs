@@ -1,6 +1,7 @@
 // src/routes/llm/regenerate_yaml.rs
 use actix_web::{post, web, HttpResponse, Responder};
 use crate::models::{AppState, Project, ProjectFile};
+use crate::services::yaml::{keyword_index, update_search_indexes};
 use crate::services::yaml::management::YamlManagement;
 use std::fs::{read_to_string, write};
 use std::path::{Path, PathBuf};
@@ -41,17 +42,24 @@ pub async fn regenerate_yaml(
             let yaml_management = YamlManagement::new();
             // Create a default LlmServiceConfig for the regeneration process
             let llm_config = LlmServiceConfig::new();
-            let combined_content_option = yaml_management.create_yaml_with_imports(
-                &project_file, 
-                &project.provider, 
-                project.specific_model.as_deref(), 
-                project.yaml_model.as_deref(), 
-                Some(llm_config) // Pass config
+            // An explicit regeneration always converts the whole file
+            let combined_content_option = yaml_management.update_yaml_with_imports(
+                &project,
+                &output_dir,
+                &project_file,
+                Some(llm_config), // Pass config
+                false,
             ).await;
 
             if let Some(combined_content) = combined_content_option {
                 match write(&yaml_path, &combined_content) {
-                    Ok(_) => HttpResponse::Ok().body(combined_content),
+                    Ok(_) => {
+                        let yaml_file_name = Path::new(yaml_path).file_name().unwrap_or_default().to_string_lossy();
+                        if let Some(source_path) = keyword_index::source_path_for_yaml(&yaml_file_name) {
+                            update_search_indexes(&output_dir, &source_path, &combined_content);
+                        }
+                        HttpResponse::Ok().body(combined_content)
+                    }
                     Err(e) => HttpResponse::InternalServerError().body(format!("Failed to write regenerated YAML to {}: {}", yaml_path, e)),
                 }
             } else {
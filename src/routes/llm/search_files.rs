@@ -1,7 +1,8 @@
 // src/routes/llm/search_files.rs
 use crate::models::AppState;
 use crate::services::project_service::ProjectService;
-use crate::services::search_service::SearchService;
+use crate::services::search_service::{SearchOptions, SearchService};
+use crate::services::vector_store::PayloadFilter;
 use crate::services::template::TemplateService;
 use crate::services::yaml::YamlService;
 use actix_web::{post, web, HttpResponse, Result};
@@ -13,6 +14,10 @@ use crate::services::utils::html_utils::escape_html;
 pub struct SearchRequest {
     project: String,
     query: String,
+    #[serde(default)]
+    rerank: bool,
+    #[serde(default)]
+    filter: Option<PayloadFilter>,
 }
 
 #[derive(Serialize)]
@@ -53,17 +58,26 @@ pub async fn search_related_files(
 
     let escaped_query_text = escape_html(req.query.clone()).await;
 
-    // Execute search
-    let num_search_results = 5;
-    let search_result = search_service.search_project(&mut project, &escaped_query_text, None, num_search_results, true).await;
+    // Execute search through the same paged search the JSON API uses
+    let options = SearchOptions {
+        query: escaped_query_text.clone(),
+        page: 1,
+        page_size: 5,
+        path_prefix: None,
+        languages: Vec::new(),
+        filter: req.filter.clone(),
+        rerank: req.rerank,
+        llm_analysis: true,
+    };
+    let search_result = search_service.search_page(&project, &output_dir, &options).await;
 
     let search_results_html = match search_result {
-        Ok((similar_files, llm_analysis)) => {
+        Ok(page) => {
             // Render search results
             template_service.render_search_results(
                 &escaped_query_text,
-                &similar_files,
-                &llm_analysis,
+                &page.results,
+                &page.llm_analysis_raw.unwrap_or_default(),
                 &project.name,
                 "transient_query_id", // A temporary query ID
             )
@@ -5,3 +5,4 @@ pub mod ui;
 pub mod query;
 pub mod git;
 pub mod analyze;
+pub mod jobs;
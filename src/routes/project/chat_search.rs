@@ -0,0 +1,45 @@
+// src/routes/project/chat_search.rs
+use crate::models::AppState;
+use crate::services::chat_search_service::ChatSearchService;
+use crate::services::project_service::ProjectService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+const DEFAULT_CHAT_SEARCH_LIMIT: usize = 10;
+const MAX_CHAT_SEARCH_LIMIT: usize = 50;
+
+#[derive(Deserialize)]
+pub struct ChatSearchQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+    /// Leave out messages from this query (e.g. the chat the user is in)
+    pub exclude_query: Option<String>,
+}
+
+/// Semantic search over the chat messages of every query in the project.
+#[get("/projects/{name}/chats/search")]
+pub async fn search_chats(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    query: web::Query<ChatSearchQuery>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    let project = match ProjectService::new().load_project(&project_dir) {
+        Ok(project) => project,
+        Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
+    };
+    if query.q.trim().is_empty() {
+        return HttpResponse::BadRequest().body("q must not be empty");
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_CHAT_SEARCH_LIMIT).clamp(1, MAX_CHAT_SEARCH_LIMIT);
+    match ChatSearchService::new()
+        .search(&project, &project_dir, &query.q, limit, query.exclude_query.as_deref())
+        .await
+    {
+        Ok(results) => HttpResponse::Ok().json(json!({ "query": query.q, "results": results })),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
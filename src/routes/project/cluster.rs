@@ -1,44 +1,46 @@
 // src/routes/project/cluster.rs
+use crate::models::AppState;
 use crate::services::clustering_service::cluster_embeddings;
-use crate::services::qdrant_service::QdrantService;
+use crate::services::project_service::ProjectService;
+use crate::services::vector_store::{self, payload_string, project_collection_name};
 use actix_web::{post, web, Error, HttpResponse};
 use serde_json::json;
-use std::env;
+use std::path::Path;
 
 #[post("/api/cluster/{project_name}")]
-pub async fn cluster_project_embeddings(path: web::Path<String>) -> Result<HttpResponse, Error> {
+pub async fn cluster_project_embeddings(
+    app_state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, Error> {
     let project_name = path.into_inner();
     println!("Received cluster request for project: {}", project_name);
 
-    // Get Qdrant URL from environment variable
-    let qdrant_server_url =
-        env::var("QDRANT_SERVER_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
+    let project_dir = Path::new(&app_state.output_dir).join(&project_name);
+    let project = ProjectService::new()
+        .load_project(&project_dir)
+        .map_err(actix_web::error::ErrorNotFound)?;
 
-    // Create a new QdrantService instance
-    let qdrant_service = QdrantService::new(&qdrant_server_url, 1536)
+    // Open the project's vector store
+    let vector_store = vector_store::open_project_store(&project, &project_dir)
         .await
         .map_err(|e| {
-            eprintln!("Failed to create Qdrant service: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to connect to Qdrant")
+            eprintln!("Failed to open vector store: {}", e);
+            actix_web::error::ErrorInternalServerError("Failed to open vector store")
         })?;
 
-    // 1. Retrieve embeddings from Qdrant
-    let search_results: Vec<(String, String, f32, Option<Vec<f32>>)> = qdrant_service
-        .search_similar_files(&project_name, vec![0.0; 1536], 1000, true)
+    // 1. Retrieve every file-level embedding from the vector store
+    let file_points = vector_store
+        .scroll_file_points(&project_collection_name(&project))
         .await
         .map_err(|e| {
             eprintln!("Error retrieving embeddings: {}", e);
-            actix_web::error::ErrorInternalServerError("Failed to retrieve embeddings from Qdrant")
+            actix_web::error::ErrorInternalServerError("Failed to retrieve embeddings from vector store")
         })?;
-    let embeddings: Vec<Vec<f32>> = search_results
-    .iter()
-    .filter_map(|(_, _, _, vectors)| vectors.as_ref().cloned()) // Extract and clone the vectors
-    .collect();
 
-    let associated_files: Vec<String> = search_results
-        .iter()
-        .filter_map(|(path, _, _, _)| path.clone().into())
-        .collect();
+    let (associated_files, embeddings): (Vec<String>, Vec<Vec<f32>>) = file_points
+        .into_iter()
+        .map(|point| (payload_string(&point.payload, "file_path"), point.vector))
+        .unzip();
 
     println!("Number of embeddings: {}", embeddings.len());
 
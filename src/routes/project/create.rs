@@ -1,6 +1,6 @@
 // src/routes/project/create.rs
 use actix_web::{post, web, HttpResponse, Responder};
-use crate::models::{AppState, Project};
+use crate::models::{AppState, Project, VectorBackend};
 use crate::services::yaml::YamlService;
 use crate::services::project_service::ProjectService;
 use std::path::Path;
@@ -11,6 +11,7 @@ struct CreateProjectForm {
     languages: String,
     source_dir: String,
     llms: String,
+    vector_backend: Option<VectorBackend>,
 }
 
 #[post("/projects")]
@@ -19,12 +20,13 @@ pub async fn create(
     form_data: web::Form<CreateProjectForm>,
 ) -> impl Responder {
     let form_data = form_data.into_inner();
-    let mut project = Project {
+    let project = Project {
         name: form_data.name.clone(),
         languages: form_data.languages.clone(),
         source_dir: form_data.source_dir.clone(),
         provider: form_data.llms.clone(),
         specific_model: None,
+        vector_backend: form_data.vector_backend.unwrap_or_default(),
         ..Default::default()
     };
 
@@ -39,10 +41,11 @@ pub async fn create(
     project_service.save_project(&project, &output_dir)
         .unwrap_or_else(|e| eprintln!("Failed to save project: {}", e));
     
+    // Indexing runs in the background; the project page shows its progress
     let yaml_service = YamlService::new();
-    yaml_service.save_yaml_files(&mut project, &app_state.output_dir, false).await;
+    yaml_service.save_yaml_files(&project_name, &app_state.output_dir, false);
     
     HttpResponse::SeeOther()
-        .append_header(("Location", "/"))
+        .append_header(("Location", format!("/projects/{}", project_name)))
         .finish()
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+// src/routes/project/duplicates.rs
+use crate::models::AppState;
+use crate::services::duplicate_service::DuplicateService;
+use crate::services::project_service::ProjectService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use std::path::Path;
+
+const DEFAULT_DUPLICATE_THRESHOLD: f32 = 0.92;
+const DEFAULT_MAX_PAIRS: usize = 25;
+
+#[derive(Deserialize)]
+pub struct DuplicatesQuery {
+    pub threshold: Option<f32>,
+    pub limit: Option<usize>,
+    /// Ask the LLM for a consolidation plan for each reported pair
+    #[serde(default)]
+    pub suggest: bool,
+}
+
+/// Near-duplicate file pairs by cosine similarity of their stored embeddings.
+#[get("/projects/{name}/duplicates")]
+pub async fn near_duplicates(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    query: web::Query<DuplicatesQuery>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    let project = match ProjectService::new().load_project(&project_dir) {
+        Ok(project) => project,
+        Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
+    };
+
+    let threshold = query.threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD);
+    if !(-1.0..=1.0).contains(&threshold) {
+        return HttpResponse::BadRequest().body("threshold must be between -1 and 1");
+    }
+
+    match DuplicateService::new()
+        .find_near_duplicates(
+            &project,
+            &project_dir,
+            threshold,
+            query.limit.unwrap_or(DEFAULT_MAX_PAIRS),
+            query.suggest,
+        )
+        .await
+    {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
@@ -0,0 +1,67 @@
+// src/routes/project/eval.rs
+use crate::models::AppState;
+use crate::services::project_service::ProjectService;
+use crate::services::search_eval::{self, EvalOptions, EvalSet};
+use actix_web::{get, post, put, web, HttpResponse, Responder};
+use std::path::Path;
+
+#[get("/projects/{name}/eval/set")]
+pub async fn get_eval_set(app_state: web::Data<AppState>, name: web::Path<String>) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    match search_eval::load_eval_set(&project_dir) {
+        Ok(eval_set) => HttpResponse::Ok().json(eval_set),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+#[put("/projects/{name}/eval/set")]
+pub async fn put_eval_set(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    eval_set: web::Json<EvalSet>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    if !project_dir.exists() {
+        return HttpResponse::NotFound().body("Project not found");
+    }
+    match search_eval::save_eval_set(&project_dir, &eval_set) {
+        Ok(()) => HttpResponse::Ok().json(eval_set.into_inner()),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+/// Runs the eval set; the body selects `k` and the embedder (`project` or `fake`).
+#[post("/projects/{name}/eval/runs")]
+pub async fn run_eval(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    options: Option<web::Json<EvalOptions>>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    let project = match ProjectService::new().load_project(&project_dir) {
+        Ok(project) => project,
+        Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
+    };
+    let options = options.map(|json| json.into_inner()).unwrap_or_default();
+
+    match search_eval::run_eval(&name, &project, &project_dir, &options).await {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+#[get("/projects/{name}/eval/runs")]
+pub async fn list_eval_runs(app_state: web::Data<AppState>, name: web::Path<String>) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    HttpResponse::Ok().json(search_eval::list_runs(&project_dir))
+}
+
+#[get("/projects/{name}/eval/runs/{run_id}")]
+pub async fn get_eval_run(app_state: web::Data<AppState>, path: web::Path<(String, String)>) -> impl Responder {
+    let (name, run_id) = path.into_inner();
+    let project_dir = Path::new(&app_state.output_dir).join(&name);
+    match search_eval::load_run(&project_dir, &run_id) {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
@@ -0,0 +1,66 @@
+// src/routes/project/export.rs
+use crate::models::AppState;
+use crate::services::project_service::ProjectService;
+use crate::services::vector_store::{self, project_collection_name};
+use actix_web::{get, web, Error, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub include_vectors: bool,
+}
+
+/// Dumps every point in the project's active collection (file and symbol points) as JSON.
+#[get("/api/export/{project_name}")]
+pub async fn export_project_vectors(
+    app_state: web::Data<AppState>,
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, Error> {
+    let project_name = path.into_inner();
+    let project_dir = Path::new(&app_state.output_dir).join(&project_name);
+    let project = ProjectService::new()
+        .load_project(&project_dir)
+        .map_err(actix_web::error::ErrorNotFound)?;
+
+    let vector_store = vector_store::open_project_store(&project, &project_dir)
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to open vector store: {}", e);
+            actix_web::error::ErrorInternalServerError("Failed to open vector store")
+        })?;
+
+    let collection_name = project_collection_name(&project);
+    let points = vector_store
+        .scroll_points(&collection_name)
+        .await
+        .map_err(|e| {
+            eprintln!("Error exporting vectors: {}", e);
+            actix_web::error::ErrorInternalServerError("Failed to read vectors from vector store")
+        })?;
+
+    let points_json: Vec<serde_json::Value> = points
+        .into_iter()
+        .map(|point| {
+            let mut point_json = json!({
+                "id": point.id,
+                "payload": point.payload,
+            });
+            if query.include_vectors {
+                point_json["vector"] = json!(point.vector);
+            }
+            point_json
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "project": project_name,
+        "collection": collection_name,
+        "embedding_config": project.embedding_config,
+        "point_count": points_json.len(),
+        "points": points_json,
+    })))
+}
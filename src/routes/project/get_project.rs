@@ -2,6 +2,7 @@
 use crate::models::AppState;
 use crate::services::project_service::ProjectService;
 use crate::services::search_service::SearchResult;
+use crate::services::rank_fusion::SourceRanks;
 use crate::services::template::TemplateService;
 use crate::services::yaml::YamlService;
 use actix_web::{get, web, HttpResponse, Responder};
@@ -27,6 +28,11 @@ pub async fn get_project(
         Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
     };
 
+    // Retry an embedding migration whose job failed (no-op if one is queued or running)
+    if project.pending_embedding_migration.is_some() {
+        yaml_service.management.start_embedding_migration(&name, &app_state.output_dir);
+    }
+
     // Check for manually updated YAML files and update embeddings if needed
     yaml_service
         .check_and_update_yaml_files(&mut project, &app_state.output_dir)
@@ -50,7 +56,7 @@ pub async fn get_project(
             query_text_for_form = query_text.clone(); // For populating the textarea
             let similar_files: Vec<SearchResult> = latest_query.vector_results
                 .iter()
-                .map(|(path, score)| SearchResult { file_path: path.clone(), file_description: None, score: *score, file_content: "".to_string(), embedding: None })
+                .map(|(path, score)| SearchResult { file_path: path.clone(), file_description: None, score: *score, file_content: "".to_string(), embedding: None, matched_symbols: Vec::new(), ranks: SourceRanks::default(), rerank: None })
                 .collect();
 
             let llm_analysis = latest_query.llm_analysis.clone();
@@ -90,6 +96,8 @@ pub async fn get_project(
     };
 
     project_service.cleanup_embeddings_on_load(&mut project, &output_dir);
+    // Check the vector store against the cleaned-up metadata in the background
+    yaml_service.management.queue_reconcile(&project.name, &app_state.output_dir);
 
     // Render full page
     let html = template_service.render_project_page(
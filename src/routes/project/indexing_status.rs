@@ -0,0 +1,17 @@
+// src/routes/project/indexing_status.rs
+use crate::services::job_service::JobService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde_json::json;
+
+/// Status of the project's most recent indexing job, polled by the project page.
+#[get("/projects/{name}/indexing-status")]
+pub async fn indexing_status(name: web::Path<String>) -> impl Responder {
+    let name = name.into_inner();
+    match JobService::new().latest_job_for_project(&name, "generate") {
+        Some(job) => {
+            let progress = job.progress();
+            HttpResponse::Ok().json(json!({ "job": job, "progress": progress }))
+        }
+        None => HttpResponse::Ok().json(json!({ "job": null })),
+    }
+}
@@ -7,6 +7,16 @@ pub mod update_settings;
 pub mod path_comment;
 pub mod update_file_yaml_override;
 pub mod cluster;
+pub mod export;
+pub mod reconcile;
+pub mod indexing_status;
+pub mod symbols;
+pub mod similar_files;
+pub mod duplicates;
+pub mod eval;
+pub mod search_api;
+pub mod chat_search;
+pub mod notes;
 pub mod git_env_settings;
 pub mod submit_query;
 
@@ -21,6 +31,24 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(update_settings::update_settings)
         .service(update_file_yaml_override::update_file_yaml_override)
         .service(cluster::cluster_project_embeddings)
+        .service(export::export_project_vectors)
+        .service(reconcile::reconcile_project_embeddings)
+        .service(indexing_status::indexing_status)
+        .service(symbols::lookup_symbols)
+        .service(similar_files::similar_files)
+        .service(duplicates::near_duplicates)
+        .service(eval::get_eval_set)
+        .service(eval::put_eval_set)
+        .service(eval::run_eval)
+        .service(eval::list_eval_runs)
+        .service(eval::get_eval_run)
+        .service(search_api::search)
+        .service(chat_search::search_chats)
+        .service(notes::list_notes)
+        .service(notes::create_note)
+        .service(notes::get_note)
+        .service(notes::update_note)
+        .service(notes::delete_note)
         .service(git_env_settings::get_git_env_settings)
         .service(git_env_settings::post_git_env_settings)
         .service(submit_query::submit_query);
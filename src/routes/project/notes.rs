@@ -0,0 +1,77 @@
+// src/routes/project/notes.rs
+use crate::models::AppState;
+use crate::services::notes_service::{NoteInput, NotesService};
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+fn project_dir(app_state: &AppState, name: &str) -> Option<PathBuf> {
+    let project_dir = Path::new(&app_state.output_dir).join(name);
+    project_dir.join("project_settings.json").exists().then_some(project_dir)
+}
+
+#[get("/projects/{name}/notes")]
+pub async fn list_notes(app_state: web::Data<AppState>, name: web::Path<String>) -> impl Responder {
+    let Some(project_dir) = project_dir(&app_state, &name) else {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    };
+    HttpResponse::Ok().json(json!({ "notes": NotesService::new().list(&project_dir) }))
+}
+
+#[post("/projects/{name}/notes")]
+pub async fn create_note(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    input: web::Json<NoteInput>,
+) -> impl Responder {
+    let Some(project_dir) = project_dir(&app_state, &name) else {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    };
+    match NotesService::new().create(&project_dir, input.into_inner()) {
+        Ok(note) => HttpResponse::Created().json(note),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+#[get("/projects/{name}/notes/{id}")]
+pub async fn get_note(app_state: web::Data<AppState>, path: web::Path<(String, String)>) -> impl Responder {
+    let (name, id) = path.into_inner();
+    let Some(project_dir) = project_dir(&app_state, &name) else {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    };
+    match NotesService::new().get(&project_dir, &id) {
+        Ok(Some(note)) => HttpResponse::Ok().json(note),
+        Ok(None) => HttpResponse::NotFound().body(format!("Note '{}' not found", id)),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+#[put("/projects/{name}/notes/{id}")]
+pub async fn update_note(
+    app_state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+    input: web::Json<NoteInput>,
+) -> impl Responder {
+    let (name, id) = path.into_inner();
+    let Some(project_dir) = project_dir(&app_state, &name) else {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    };
+    match NotesService::new().update(&project_dir, &id, input.into_inner()) {
+        Ok(Some(note)) => HttpResponse::Ok().json(note),
+        Ok(None) => HttpResponse::NotFound().body(format!("Note '{}' not found", id)),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+#[delete("/projects/{name}/notes/{id}")]
+pub async fn delete_note(app_state: web::Data<AppState>, path: web::Path<(String, String)>) -> impl Responder {
+    let (name, id) = path.into_inner();
+    let Some(project_dir) = project_dir(&app_state, &name) else {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    };
+    match NotesService::new().delete(&project_dir, &id) {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().body(format!("Note '{}' not found", id)),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
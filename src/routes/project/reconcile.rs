@@ -0,0 +1,33 @@
+// src/routes/project/reconcile.rs
+use crate::models::AppState;
+use crate::services::job_service::{JobKind, JobService};
+use actix_web::{post, web, Error, HttpResponse};
+use serde_json::json;
+use std::path::Path;
+
+/// Queues a job reconciling project embedding metadata with the vector store and returns
+/// its id; the job's log and `reconcile_report.json` hold the diff. A reconcile job that is
+/// already queued or running is returned instead of queueing another.
+#[post("/api/reconcile/{project_name}")]
+pub async fn reconcile_project_embeddings(
+    app_state: web::Data<AppState>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+    let project_name = path.into_inner();
+    if !Path::new(&app_state.output_dir).join(&project_name).join("project_settings.json").exists() {
+        return Err(actix_web::error::ErrorNotFound(format!("Project {} not found", project_name)));
+    }
+
+    let job_service = JobService::new();
+    let (job_id, queued) = match job_service.enqueue_unique(&app_state.output_dir, &project_name, JobKind::Reconcile) {
+        Some(job_id) => (job_id, true),
+        None => {
+            let active = job_service
+                .latest_job_for_project(&project_name, JobKind::Reconcile.name())
+                .ok_or_else(|| actix_web::error::ErrorInternalServerError("Reconcile job not found"))?;
+            (active.id, false)
+        }
+    };
+
+    Ok(HttpResponse::Accepted().json(json!({ "job_id": job_id, "queued": queued })))
+}
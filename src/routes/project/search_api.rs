@@ -0,0 +1,88 @@
+// src/routes/project/search_api.rs
+use crate::models::AppState;
+use crate::services::project_service::ProjectService;
+use crate::services::rank_fusion::SourceRanks;
+use crate::services::rerank_service::RerankScore;
+use crate::services::search_service::{parse_llm_search_analysis, LlmSearchAnalysis, SearchOptions, SearchService};
+use crate::services::vector_store::SymbolHit;
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Serialize;
+use std::path::Path;
+
+/// Bumped on breaking changes to the response shape; the route path carries the major version.
+const SEARCH_API_VERSION: &str = "1";
+
+#[derive(Serialize)]
+struct SearchResultJson {
+    file_path: String,
+    score: f32,
+    description: Option<String>,
+    ranks: SourceRanks,
+    matched_symbols: Vec<SymbolHit>,
+    rerank: Option<RerankScore>,
+}
+
+#[derive(Serialize)]
+struct SearchResponseJson {
+    version: &'static str,
+    query: String,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    results: Vec<SearchResultJson>,
+    /// Present when `llm_analysis` was requested and the reply parsed
+    llm_analysis: Option<LlmSearchAnalysis>,
+    /// Error parsing the LLM reply, if any
+    llm_analysis_error: Option<String>,
+}
+
+/// JSON search for scripts and tools: hybrid ranking with per-source ranks, matched
+/// symbols, paging and path/language filters. Nothing is written to the query history.
+#[post("/api/v1/projects/{name}/search")]
+pub async fn search(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    options: web::Json<SearchOptions>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    let project = match ProjectService::new().load_project(&project_dir) {
+        Ok(project) => project,
+        Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
+    };
+    if options.query.trim().is_empty() {
+        return HttpResponse::BadRequest().body("query must not be empty");
+    }
+
+    let page = match SearchService::new().search_page(&project, &project_dir, &options).await {
+        Ok(page) => page,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+
+    let (llm_analysis, llm_analysis_error) = match page.llm_analysis_raw.map(parse_llm_search_analysis) {
+        Some(Ok(analysis)) => (Some(analysis), None),
+        Some(Err(e)) => (None, Some(e)),
+        None => (None, None),
+    };
+
+    HttpResponse::Ok().json(SearchResponseJson {
+        version: SEARCH_API_VERSION,
+        query: options.into_inner().query,
+        page: page.page,
+        page_size: page.page_size,
+        total: page.total,
+        results: page
+            .results
+            .into_iter()
+            .map(|result| SearchResultJson {
+                file_path: result.file_path,
+                score: result.score,
+                description: result.file_description,
+                ranks: result.ranks,
+                matched_symbols: result.matched_symbols,
+                rerank: result.rerank,
+            })
+            .collect(),
+        llm_analysis,
+        llm_analysis_error,
+    })
+}
@@ -0,0 +1,59 @@
+// src/routes/project/similar_files.rs
+use crate::models::AppState;
+use crate::services::project_service::ProjectService;
+use crate::services::search_service::SearchService;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+const DEFAULT_SIMILAR_LIMIT: u64 = 5;
+
+#[derive(Deserialize)]
+pub struct SimilarFilesQuery {
+    pub file: String,
+    pub dir: Option<String>,
+    pub limit: Option<u64>,
+}
+
+/// Files nearest to `file` by its stored embedding ("more like this").
+#[get("/projects/{name}/similar")]
+pub async fn similar_files(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    query: web::Query<SimilarFilesQuery>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    let project = match ProjectService::new().load_project(&project_dir) {
+        Ok(project) => project,
+        Err(e) => return HttpResponse::NotFound().body(format!("Project not found: {}", e)),
+    };
+
+    let results = SearchService::new()
+        .similar_to_file(
+            &project,
+            &query.file,
+            &project_dir,
+            query.dir.as_deref(),
+            query.limit.unwrap_or(DEFAULT_SIMILAR_LIMIT),
+        )
+        .await;
+
+    match results {
+        Ok(results) => {
+            let files: Vec<_> = results
+                .iter()
+                .map(|result| {
+                    json!({
+                        "file_path": result.file_path,
+                        "score": result.score,
+                        "file_description": result.file_description,
+                        "matched_symbols": result.matched_symbols,
+                    })
+                })
+                .collect();
+            HttpResponse::Ok().json(json!({ "file": query.file, "similar": files }))
+        }
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -9,6 +9,7 @@ use crate::services::utils::html_utils::escape_html;
 #[derive(serde::Deserialize)]
 pub struct SubmitQueryForm {
     q: String,
+    rerank: Option<bool>,
 }
 
 #[post("/projects/{name}")]
@@ -36,7 +37,7 @@ pub async fn submit_query(
     if !escaped_query_text.is_empty() {
         // Execute new search. The results are saved within search_project.
         let num_search_results = 5;
-        if let Err(e) = search_service.search_project(&mut project, &escaped_query_text, Some(&output_dir), num_search_results, true).await {
+        if let Err(e) = search_service.search_project(&mut project, &escaped_query_text, &output_dir, Some(&output_dir), num_search_results, true, form.rerank.unwrap_or(false), None).await {
             // Log the error, but still redirect. A more advanced implementation might use flash messages.
             eprintln!("Error during search for project '{}': {}", name, e);
         }
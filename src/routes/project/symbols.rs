@@ -0,0 +1,32 @@
+// src/routes/project/symbols.rs
+use crate::models::AppState;
+use crate::services::yaml::symbol_index;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+const DEFAULT_SYMBOL_LIMIT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct SymbolQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+/// Looks up a symbol by exact, prefix or fuzzy name and returns where it is defined
+/// and which functions call it.
+#[get("/projects/{name}/symbols")]
+pub async fn lookup_symbols(
+    app_state: web::Data<AppState>,
+    name: web::Path<String>,
+    query: web::Query<SymbolQuery>,
+) -> impl Responder {
+    let project_dir = Path::new(&app_state.output_dir).join(name.as_str());
+    if !project_dir.join("project_settings.json").exists() {
+        return HttpResponse::NotFound().body(format!("Project '{}' not found", name));
+    }
+
+    let matches = symbol_index::lookup(&project_dir, &query.q, query.limit.unwrap_or(DEFAULT_SYMBOL_LIMIT));
+    HttpResponse::Ok().json(json!({ "query": query.q, "matches": matches }))
+}
@@ -4,7 +4,7 @@ use serde::Deserialize;
 use std::fs::{read_to_string, write};
 use std::path::Path;
 use crate::models::{AppState, Project};
-use crate::services::yaml::management::YamlManagement; 
+use crate::services::job_service::{JobKind, JobService};
 
 #[derive(Deserialize, Debug)]
 pub struct FileYamlOverride {
@@ -34,10 +34,12 @@ pub async fn update_file_yaml_override(
                     match serde_json::to_string_pretty(&project) {
                         Ok(updated_project_settings_json) => {
                             if write(&project_settings_path, updated_project_settings_json).is_ok() {
-                                // Trigger embedding regeneration
-                                let yaml_management = &YamlManagement::new(); //Access YamlManagement from app state, or create it here
-
-                                yaml_management.regenerate_embedding(&mut project, file_path, &app_state.output_dir).await;
+                                // Regenerate the file's embedding (and YAML, if now needed) in the background
+                                JobService::new().enqueue(
+                                    &app_state.output_dir,
+                                    project_name,
+                                    JobKind::RegenerateFile { file_path: file_path.clone() },
+                                );
                                 HttpResponse::Ok().json(format!("YAML override updated for {}", file_path))
                             } else {
                                 HttpResponse::InternalServerError().body("Failed to write updated project settings")
@@ -1,12 +1,12 @@
 // src/routes/project/update_settings.rs
 use actix_web::{post, web, HttpResponse, Responder};
-use crate::models::AppState;
+use crate::models::{AppState, EmbeddingConfig, EmbeddingProviderKind, VectorBackend};
+use crate::services::yaml::management::migration;
+use crate::services::job_service::{JobKind, JobService};
 use crate::services::project_service::ProjectService;
 use crate::services::yaml::management::YamlManagement;
 use std::path::Path;
 use serde::Deserialize;
-use actix_rt::spawn;
-use std::sync::{Arc, Mutex};
 
 #[derive(Deserialize, Debug)]
 pub struct ProjectSettings {
@@ -16,6 +16,17 @@ pub struct ProjectSettings {
     pub specific_model: Option<String>,
     pub yaml_model: Option<String>, // New YAML model field
     pub git_integration_enabled: Option<bool>,
+    pub chat_memory_enabled: Option<bool>,
+    pub rust_skeleton_only: Option<bool>,
+    pub vector_backend: Option<VectorBackend>,
+    pub embedding_provider: Option<EmbeddingProviderKind>,
+    pub embedding_model: Option<String>,
+    pub embedding_base_url: Option<String>,
+    pub embedding_dimensions: Option<u32>,
+    pub rrf_k: Option<f32>,
+    pub vector_weight: Option<f32>,
+    pub keyword_weight: Option<f32>,
+    pub symbol_weight: Option<f32>,
 }
 
 #[post("/update/{name}/settings")]
@@ -26,7 +37,6 @@ pub async fn update_settings(
 ) -> impl Responder {
     let name = name.into_inner();
     let output_dir = Path::new(&app_state.output_dir).join(&name);
-    let app_state_arc = Arc::new(app_state.get_ref());
 
     let project_service = ProjectService::new();
     let yaml_management = YamlManagement::new();
@@ -35,6 +45,7 @@ pub async fn update_settings(
     match project_service.load_project(&output_dir) {
         Ok(mut project) => {
             let old_default_use_yaml = project.default_use_yaml;
+            let old_vector_backend = project.vector_backend;
             // Update project settings
             project.languages = form.languages.clone();
             project.provider = form.provider.clone();
@@ -42,35 +53,71 @@ pub async fn update_settings(
             project.specific_model = form.specific_model.clone();
             project.yaml_model = form.yaml_model.clone(); // Save the new YAML model
             project.git_integration_enabled = form.git_integration_enabled.unwrap_or(false);
+            project.chat_memory_enabled = form.chat_memory_enabled.unwrap_or(false);
+            project.rust_skeleton_only = form.rust_skeleton_only.unwrap_or(false);
+            if let Some(vector_backend) = form.vector_backend {
+                project.vector_backend = vector_backend;
+            }
             let new_default_use_yaml = project.default_use_yaml;
 
+            let hybrid = &mut project.hybrid_search;
+            if let Some(rrf_k) = form.rrf_k.filter(|k| *k > 0.0) {
+                hybrid.rrf_k = rrf_k;
+            }
+            for (weight, value) in [
+                (&mut hybrid.vector_weight, form.vector_weight),
+                (&mut hybrid.keyword_weight, form.keyword_weight),
+                (&mut hybrid.symbol_weight, form.symbol_weight),
+            ] {
+                if let Some(value) = value.filter(|w| *w >= 0.0) {
+                    *weight = value;
+                }
+            }
+
+            // A new embedding model/dimension is built in a fresh collection in the background;
+            // the current one keeps serving searches until the swap.
+            let requested_config = EmbeddingConfig {
+                provider: form.embedding_provider.unwrap_or(project.embedding_config.provider),
+                model: form.embedding_model.clone()
+                    .filter(|m| !m.trim().is_empty())
+                    .map(|m| m.trim().to_string())
+                    .unwrap_or_else(|| project.embedding_config.model.clone()),
+                dimensions: form.embedding_dimensions.unwrap_or(project.embedding_config.dimensions),
+                base_url: match &form.embedding_base_url {
+                    Some(url) => Some(url.trim().to_string()).filter(|url| !url.is_empty()),
+                    None => project.embedding_config.base_url.clone(),
+                },
+            };
+            if requested_config.provider == EmbeddingProviderKind::OpenAiCompatible && requested_config.base_url.is_none() {
+                return HttpResponse::BadRequest().body("An OpenAI-compatible embedding provider needs a base URL");
+            }
+            let target_config = project.pending_embedding_migration.as_ref()
+                .map(|m| m.config.clone())
+                .unwrap_or_else(|| project.embedding_config.clone());
+            let start_migration = requested_config != target_config;
+            if start_migration {
+                if requested_config == project.embedding_config {
+                    project.pending_embedding_migration = None; // Reverted; any running migration discards itself
+                } else {
+                    project.pending_embedding_migration = Some(migration::new_migration(&project, requested_config));
+                }
+            }
+
             // Save updated project
             if let Err(e) = project_service.save_project(&project, &output_dir) {
                 return HttpResponse::InternalServerError()
                     .body(format!("Failed to update project settings: {}", e));
             }
 
-            // Clone necessary data for the background task
-           let output_dir_str = app_state_arc.output_dir.clone();
-           let arc_yaml_management = Arc::new(yaml_management);
-
-           // Wrap the project in a Mutex *before* putting it in the Arc
-           let project_mutex = Arc::new(Mutex::new(project.clone()));
-
-           // Check if default_use_yaml has changed and trigger re-embedding
-           if old_default_use_yaml != new_default_use_yaml {
-               let project_mutex_clone = Arc::clone(&project_mutex);
-                // Spawn a background task using actix_rt::spawn
-               spawn(async move {
-                   println!("default_use_yaml setting changed, regenerating embeddings in background...");
+            if start_migration && project.pending_embedding_migration.is_some() {
+                yaml_management.start_embedding_migration(&name, &app_state.output_dir);
+            }
 
-                   let mut project = project_mutex_clone.lock().unwrap();
-                   // Iterate through files and regenerate embeddings
-                   for file_path in project.embeddings.keys().cloned().collect::<Vec<String>>() {
-                    arc_yaml_management.regenerate_embedding(&mut *project, &file_path, &output_dir_str).await; // Access the project through the MutexGuard
-                }
-               });
-           }
+            // Re-embed everything in a queued job if the YAML default or the vector store changed
+            if old_default_use_yaml != new_default_use_yaml || old_vector_backend != project.vector_backend {
+                println!("Embedding settings changed, queueing re-embedding of all files...");
+                JobService::new().enqueue(&app_state.output_dir, &name, JobKind::ReembedAll);
+            }
 
             // Redirect back to the project page
             HttpResponse::SeeOther()
@@ -17,7 +17,7 @@ pub async fn update(
     let project_settings_path = output_dir.join("project_settings.json");
 
     if let Ok(project_settings_json) = read_to_string(project_settings_path) {
-        if let Ok(mut project) = serde_json::from_str::<Project>(&project_settings_json) {
+        if let Ok(project) = serde_json::from_str::<Project>(&project_settings_json) {
 
             let project_service = ProjectService::new();
             if let Err(e) = project_service.save_project(&project, &output_dir) {
@@ -26,7 +26,7 @@ pub async fn update(
             }
 
             let yaml_service = YamlService::new();
-            yaml_service.save_yaml_files(&mut project, &app_state.output_dir, query.force.unwrap_or(false)).await;
+            yaml_service.save_yaml_files(&name, &app_state.output_dir, query.force.unwrap_or(false));
 
             // Redirect back to the project page
             HttpResponse::SeeOther()
@@ -41,12 +41,13 @@ pub async fn get_architect_decision(
     architect_prompt.push_str("{\n  \"action\": \"GENERATE\" | \"FETCH_SOURCE\" | \"SEARCH_MORE\",\n");
     architect_prompt.push_str("  \"reason\": \"A brief explanation for your decision.\",\n");
     architect_prompt.push_str("  \"paths\": [\"path/to/file1.rs\", \"path/to/file2.rs\"], // ONLY if action is FETCH_SOURCE\n");
-    architect_prompt.push_str("  \"keywords\": \"space separated keywords for new search\" // ONLY if action is SEARCH_MORE\n}\n\n");
+    architect_prompt.push_str("  \"keywords\": \"space separated keywords for new search\", // ONLY if action is SEARCH_MORE\n");
+    architect_prompt.push_str("  \"filter\": \"optional filter expression\" // ONLY if action is SEARCH_MORE\n}\n\n");
 
     architect_prompt.push_str("Available Actions:\n");
     architect_prompt.push_str("- `GENERATE`: You have sufficient context to directly answer the user's latest message.\n");
     architect_prompt.push_str("- `FETCH_SOURCE`: You need the full source code for specific files whose YAML summaries are available. Provide a list of relative file paths (e.g., 'src/models.rs') in the 'paths' array. Only select files whose YAML maps are available.\n");
-    architect_prompt.push_str("- `SEARCH_MORE`: You need to broaden or refine the search for YAML maps. Provide a new set of space-separated keywords in the 'keywords' field.\n\n");
+    architect_prompt.push_str("- `SEARCH_MORE`: You need to broaden or refine the search for YAML maps. Provide a new set of space-separated keywords in the 'keywords' field. Optionally narrow it with a 'filter' expression of space separated terms such as `path:src/services/**`, `ext:rs`, `lang:python`, `lines:<500`, with a leading '-' to exclude (e.g. `-path:**/tests/**`).\n\n");
     architect_prompt.push_str("Remember, your response MUST be a single JSON object. Start with '{' and end with '}'.\n");
 
     let llm_config_option = Some(LlmServiceConfig::new()); // No grounding for architect decision
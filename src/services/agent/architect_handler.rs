@@ -4,6 +4,7 @@ use crate::models::Project;
 use crate::services::llm_service::LlmService;
 use crate::services::agent::architect;
 use crate::services::agent::state::{AgentState, AgentContext};
+use crate::services::vector_store::PayloadFilter;
 
 /// Handles the architect's decision-making process using the state machine.
 /// Returns the next AgentState based on the architect's decision.
@@ -85,7 +86,18 @@ pub async fn handle_architect_decision_stateful(
                 return Ok(AgentState::ReadyToGenerate);
             }
 
-            Ok(AgentState::SearchingMore(new_keywords))
+            let filter = match architect_decision["filter"].as_str().filter(|expr| !expr.trim().is_empty()) {
+                Some(expression) => match PayloadFilter::parse(expression) {
+                    Ok(filter) => Some(filter),
+                    Err(e) => {
+                        context.add_thought(format!("Ignoring invalid SEARCH_MORE filter '{}': {}", expression, e));
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            Ok(AgentState::SearchingMore(new_keywords, filter))
         },
         "GENERATE" => {
             context.add_thought("Architect decided to GENERATE directly.".to_string());
@@ -68,6 +68,7 @@ pub async fn handle_agentic_message(
                     &search_service,
                     &yaml_service,
                     project,
+                    query_id,
                     &initial_query,
                     user_message_content_raw,
                     previous_history_for_llm,
@@ -95,12 +96,13 @@ pub async fn handle_agentic_message(
                 ).await?
             },
 
-            AgentState::SearchingMore(keywords) => {
+            AgentState::SearchingMore(keywords, filter) => {
                 handle_search_more(
                     &yaml_service,
                     project,
                     &project_dir,
                     keywords,
+                    filter,
                     &mut context,
                 ).await?
             },
@@ -130,6 +132,8 @@ pub async fn handle_agentic_message(
         hidden_context,
         &context.file_contents_map,
         &context.yaml_summaries,
+        &context.memory_snippets,
+        &context.notes,
         &project_dir,
         &mut context.thoughts,
     ).await?;
@@ -5,6 +5,7 @@ use std::path::Path;
 
 use crate::models::{ChatMessage, Project};
 use crate::services::llm_service::{LlmService, LlmServiceConfig};
+use crate::services::notes_service::ProjectNote;
 use crate::services::project_service::query_management::QueryManager;
 use crate::routes::llm::chat_analysis::utils::{create_system_prompt, format_messages_for_llm}; // Import necessary utilities
 
@@ -23,6 +24,8 @@ pub async fn generate_llm_response(
     hidden_context: Vec<String>,
     current_file_contents_map: &HashMap<String, String>,
     current_initial_proactive_yaml_summaries: &HashMap<String, String>,
+    memory_snippets: &[String],
+    notes: &[ProjectNote],
     project_dir: &Path,
     thoughts: &mut Vec<String>,
 ) -> Result<ChatMessage, String> {
@@ -73,13 +76,21 @@ pub async fn generate_llm_response(
     let query_text = query_manager
         .get_query_data_field(project_dir, query_id, "query")
         .unwrap_or_else(|| "No previous query found".to_string());
-    let system_prompt = create_system_prompt(
+    let mut system_prompt = create_system_prompt(
         &query_text,
         &context_files, // These are the full content files
         &file_contents_for_llm, // Full file contents
         &Project { file_descriptions: combined_project_file_descriptions, ..project.clone() }, // Pass a clone of project with augmented descriptions
         include_file_descriptions,
+        notes,
     );
+    if !memory_snippets.is_empty() {
+        system_prompt.push_str("\n\nProject memory - excerpts from earlier discussions about this project that may be relevant. \
+            They can be outdated; prefer the current code when they disagree:\n");
+        for snippet in memory_snippets {
+            system_prompt.push_str(&format!("\n---\n{}\n", snippet));
+        }
+    }
     thoughts.push("System prompt created.".to_string());
 
     // Create the current user message (the one the LLM is responding to)
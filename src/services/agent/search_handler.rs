@@ -2,11 +2,10 @@
 
 use crate::models::{ChatMessage, Project};
 use crate::services::llm_service::{LlmService, LlmServiceConfig};
-use crate::services::search_service::{SearchService, SearchResult};
+use crate::services::search_service::{parse_llm_search_analysis, SearchService, SearchResult};
+use crate::services::vector_store::PayloadFilter;
 use crate::services::yaml::YamlService;
-use crate::services::utils::html_utils::unescape_html;
 use std::collections::HashSet;
-use serde_json::Value;
 use std::path::Path;
 
 /// Generates a comprehensive query string for semantic (vector) search,
@@ -42,37 +41,42 @@ pub fn generate_contextual_vector_query(
     query
 }
 
-/// Parses the raw LLM analysis response to extract suggested files and BM25 keywords.
-fn parse_llm_search_analysis(
-    llm_analysis_raw: String,
-) -> Result<(HashSet<String>, String), String> {
-    let llm_analysis_unescaped = unescape_html(llm_analysis_raw);
-    let llm_analysis_json_str = llm_analysis_unescaped
-        .split("```json")
-        .nth(1)
-        .and_then(|s| s.split("```").next())
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| "".to_string());
-
-    let llm_analysis_json: Value = serde_json::from_str(&llm_analysis_json_str)
-        .map_err(|e| format!("Failed to parse LLM analysis JSON: {}\nFailed data: {}\nSource: {}", e, llm_analysis_json_str, llm_analysis_unescaped))?;
-    
-    let suggested_vector_files: HashSet<String> = llm_analysis_json["suggested_files"]
-        .as_array()
-        .unwrap_or(&vec![])
-        .iter()
-        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-        .collect();
-
-    let bm25_keywords_str = llm_analysis_json["bm25_keywords"]
-        .as_str()
-        .unwrap_or("")
-        .to_string();
+/// Reads a search filter from a `filter: <expression>` line in the user's message, e.g.
+/// `filter: path:src/services/** ext:rs -path:**/tests/**`. Invalid expressions are logged and ignored.
+pub fn extract_search_filter(user_message_content: &str, thoughts: &mut Vec<String>) -> Option<PayloadFilter> {
+    let expression = user_message_content.lines().find_map(|line| {
+        let line = line.trim();
+        line.get(..7)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("filter:"))
+            .map(|_| line[7..].trim())
+    })?;
+    match PayloadFilter::parse(expression) {
+        Ok(filter) if !filter.is_empty() => {
+            thoughts.push(format!("Restricting searches with filter: '{}'", expression));
+            Some(filter)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            thoughts.push(format!("Ignoring invalid search filter '{}': {}", expression, e));
+            None
+        }
+    }
+}
 
-    Ok((suggested_vector_files, bm25_keywords_str))
+/// Keeps only the keyword hits whose file matches `filter`.
+pub fn filter_keyword_hits(
+    project: &Project,
+    hits: Vec<(String, f32)>,
+    filter: Option<&PayloadFilter>,
+) -> Vec<(String, f32)> {
+    match filter {
+        Some(filter) => hits.into_iter().filter(|(path, _)| filter.matches_file(project, path)).collect(),
+        None => hits,
+    }
 }
 
 /// Performs the initial hybrid search (vector + BM25F) to gather relevant file context.
+/// With a `filter`, both searches only return files whose metadata matches it.
 #[allow(clippy::too_many_arguments)]
 pub async fn perform_initial_hybrid_search(
     llm_service: &LlmService,
@@ -84,6 +88,7 @@ pub async fn perform_initial_hybrid_search(
     previous_history_for_llm: &Vec<ChatMessage>,
     llm_config: &LlmServiceConfig,
     project_dir: &Path,
+    filter: Option<&PayloadFilter>,
     thoughts: &mut Vec<String>,
 ) -> Result<(Vec<SearchResult>, Vec<(String, f32)>, HashSet<String>, String), String> {
     // 1. Generate Contextual Query for Vector Search
@@ -98,32 +103,36 @@ pub async fn perform_initial_hybrid_search(
     let detailed_vector_query = llm_service.get_analysis(&contextual_vector_query_llm_input, &project.provider.clone(), project.specific_model.as_deref(), Some(llm_config.clone())).await;
     thoughts.push(format!("Detailed Vector Query from LLM: '{}'", detailed_vector_query));
 
-    // 2. Primary Vector Search
-    thoughts.push("Performing primary vector search over project embeddings.".to_string());
+    // 2. Primary hybrid search (vector, keyword and symbol sources fused with RRF)
+    thoughts.push("Performing primary hybrid search over project embeddings and the keyword index.".to_string());
     let num_vector_results = 5; // Get top 5 vector results
     let (vector_search_results, llm_analysis_raw) = search_service.search_project(
         &mut project.clone(),
         &detailed_vector_query,
+        project_dir,
         None,
         num_vector_results,
         true, // Enable LLM analysis for this internal call (suggested files & BM25 keywords)
+        false, // The agent's own LLM analysis already judges the results
+        filter,
     ).await?;
-    thoughts.push(format!("Vector search returned {} results.", vector_search_results.len()));
+    thoughts.push(format!("Hybrid search returned {} results.", vector_search_results.len()));
 
     // Parse LLM analysis for suggested files and BM25 keywords
-    let (suggested_vector_files, bm25_keywords_str) = parse_llm_search_analysis(llm_analysis_raw)?;
+    let analysis = parse_llm_search_analysis(llm_analysis_raw)?;
+    let suggested_vector_files: HashSet<String> = analysis.suggested_files.into_iter().collect();
+    let bm25_keywords_str = analysis.bm25_keywords;
     thoughts.push(format!("LLM Analysis suggested {} files for full source from vector search.", suggested_vector_files.len()));
     thoughts.push(format!("LLM Analysis provided BM25 keywords: '{}'", bm25_keywords_str));
 
     // 3. Secondary BM25F Search (using keywords from LLM Analysis)
     thoughts.push("Performing secondary BM25F search over YAML summaries using LLM-generated keywords.".to_string());
     let num_bm25_results = 25; // Get top 25 BM25F results
-    let bm25f_results = yaml_service.bm25f_search(
+    let bm25f_results = filter_keyword_hits(
         project,
-        &bm25_keywords_str,
-        project_dir,
-        num_bm25_results,
-    ).await?;
+        yaml_service.bm25f_search(&bm25_keywords_str, project_dir, num_bm25_results)?,
+        filter,
+    );
     thoughts.push(format!("BM25F search returned {} results.", bm25f_results.len()));
 
     Ok((vector_search_results, bm25f_results, suggested_vector_files, bm25_keywords_str))
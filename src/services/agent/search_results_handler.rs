@@ -35,23 +35,17 @@ pub fn consolidate_search_results(
         } else {
             thoughts.push(format!("Could not normalize path from raw vector search results: {}", search_result.file_path));
         }
+        thoughts.push(format!("Hybrid search found {} via {}", search_result.file_path, search_result.ranks.describe()));
+        if !search_result.matched_symbols.is_empty() {
+            let symbol_names: Vec<&str> = search_result.matched_symbols.iter().map(|s| s.name.as_str()).collect();
+            thoughts.push(format!("Vector search matched symbols in {}: {}", search_result.file_path, symbol_names.join(", ")));
+        }
     }
 
     // Add paths from bm25f_results
+    // BM25F results are already keyed by source path
     for (file_path, _) in bm25f_results {
-        // split on project_name/ and get the second item of the split
-        let yaml_target_path = file_path.split(&format!("{}/", project.name))
-            .nth(1);
-        let final_yaml_target_path = match yaml_target_path {
-            Some(p) => {
-                p.replace("*", "/").replace(".yml", "") // Reverse the earlier replacement to get original path
-            },
-            None => {
-                thoughts.push(format!("Could not extract target path from YAML hit: {}", file_path));
-                continue; // Skip this hit
-            },
-        };
-        all_relevant_file_paths.insert(final_yaml_target_path.clone());
+        all_relevant_file_paths.insert(file_path.clone());
     }
 
     all_relevant_file_paths
@@ -4,7 +4,10 @@ use std::path::Path;
 use crate::services::path_utils::PathUtils;
 use crate::models::{ChatMessage, Project};
 use crate::services::llm_service::{LlmService, LlmServiceConfig};
+use crate::services::chat_search_service::ChatSearchService;
+use crate::services::notes_service::{NotesService, ProjectNote};
 use crate::services::search_service::SearchService;
+use crate::services::vector_store::PayloadFilter;
 use crate::services::yaml::YamlService;
 use super::{architect_handler, file_context, search_handler, search_results_handler};
 
@@ -17,7 +20,7 @@ pub enum AgentState {
     /// Architect decided to fetch specific source files
     FetchingSource(Vec<String>),
     /// Architect decided to search for more YAML summaries
-    SearchingMore(String), // Keywords for search
+    SearchingMore(String, Option<PayloadFilter>), // Keywords for search, and a filter replacing the message's
     /// Ready to generate final response
     ReadyToGenerate,
     /// Terminal error state
@@ -36,6 +39,12 @@ pub struct AgentContext {
     pub turn_count: usize,
     /// Maximum allowed turns before forcing generation
     pub max_turns: usize,
+    /// Filter from a `filter:` line in the user's message, applied to every search
+    pub search_filter: Option<PayloadFilter>,
+    /// Relevant messages from the project's other chats ("project memory")
+    pub memory_snippets: Vec<String>,
+    /// Project notes relevant to the user's message
+    pub notes: Vec<ProjectNote>,
 }
 
 impl AgentContext {
@@ -46,6 +55,9 @@ impl AgentContext {
             thoughts: Vec::new(),
             turn_count: 0,
             max_turns,
+            search_filter: None,
+            memory_snippets: Vec::new(),
+            notes: Vec::new(),
         }
     }
 
@@ -89,12 +101,19 @@ impl AgentContext {
 }
 
 
+/// How many past chat messages are pulled in as project memory.
+const MEMORY_SNIPPETS: usize = 3;
+/// Characters kept from each project memory message.
+const MEMORY_SNIPPET_CHARS: usize = 1_500;
+
 /// Handles the initial hybrid search state
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_initial_search(
     llm_service: &LlmService,
     search_service: &SearchService,
     yaml_service: &YamlService,
     project: &Project,
+    query_id: &str,
     initial_query: &str,
     user_message_content_raw: &str,
     previous_history_for_llm: &Vec<ChatMessage>,
@@ -103,6 +122,7 @@ pub async fn handle_initial_search(
     context: &mut AgentContext,
 ) -> Result<AgentState, String> {
     context.add_thought("--- PHASE 1: Initial Hybrid Search ---".to_string());
+    context.search_filter = search_handler::extract_search_filter(user_message_content_raw, &mut context.thoughts);
 
     let (vector_search_results, bm25f_results, suggested_vector_files, _bm25_keywords_str) =
         search_handler::perform_initial_hybrid_search(
@@ -115,6 +135,7 @@ pub async fn handle_initial_search(
             previous_history_for_llm,
             llm_config,
             project_dir,
+            context.search_filter.as_ref(),
             &mut context.thoughts,
         ).await?;
 
@@ -163,10 +184,54 @@ pub async fn handle_initial_search(
         context.yaml_summaries.len()
     ));
 
+    match NotesService::new().relevant_notes(project, project_dir, user_message_content_raw).await {
+        Ok(notes) => {
+            context.add_thought(format!("Found {} relevant project notes.", notes.len()));
+            context.notes = notes;
+        }
+        Err(e) => context.add_thought(format!("Project notes lookup failed: {}", e)),
+    }
+
+    if project.chat_memory_enabled {
+        load_project_memory(project, query_id, user_message_content_raw, project_dir, context).await;
+    }
+
     // Transition to architect decision
     Ok(AgentState::ArchitectDecision)
 }
 
+/// Searches the project's other chats for messages relevant to the user's message.
+async fn load_project_memory(
+    project: &Project,
+    query_id: &str,
+    user_message_content_raw: &str,
+    project_dir: &Path,
+    context: &mut AgentContext,
+) {
+    let hits = match ChatSearchService::new()
+        .search(project, project_dir, user_message_content_raw, MEMORY_SNIPPETS, Some(query_id))
+        .await
+    {
+        Ok(hits) => hits,
+        Err(e) => {
+            context.add_thought(format!("Project memory search failed: {}", e));
+            return;
+        }
+    };
+
+    for hit in hits {
+        let snippet: String = hit.snippet.chars().take(MEMORY_SNIPPET_CHARS).collect();
+        context.memory_snippets.push(format!(
+            "From \"{}\" ({}, {}):\n{}",
+            hit.query_title,
+            hit.role,
+            hit.timestamp.as_deref().unwrap_or("unknown time"),
+            snippet
+        ));
+    }
+    context.add_thought(format!("Added {} project memory snippets from past chats.", context.memory_snippets.len()));
+}
+
 /// Handles an architect decision turn
 pub async fn handle_architect_turn(
     llm_service: &LlmService,
@@ -227,43 +292,36 @@ pub async fn handle_search_more(
     project: &Project,
     project_dir: &Path,
     keywords: String,
+    filter: Option<PayloadFilter>,
     context: &mut AgentContext,
 ) -> Result<AgentState, String> {
     context.add_thought(format!("--- SEARCH_MORE: '{}' ---", keywords));
 
     let num_refined_yaml_results = 10;
-    let refined_yaml_hits = yaml_service.bm25f_search(
+    let filter = filter.or_else(|| context.search_filter.clone());
+    let refined_yaml_hits = search_handler::filter_keyword_hits(
         project,
-        &keywords,
-        project_dir,
-        num_refined_yaml_results,
-    ).await?;
+        yaml_service.bm25f_search(&keywords, project_dir, num_refined_yaml_results)?,
+        filter.as_ref(),
+    );
 
     context.add_thought(format!("Refined BM25F search returned {} results.", refined_yaml_hits.len()));
 
-    for (path, _) in &refined_yaml_hits {
-        let yaml_target_path = path.split(&format!("{}/", project.name)).nth(1);
-        let final_yaml_target_path = match yaml_target_path {
-            Some(p) => p.replace("*", "/").replace(".yml", ""),
-            None => {
-                context.add_thought(format!("Could not extract target path from YAML hit: {}", path));
-                continue;
-            },
-        };
-
+    // Hits are keyed by source path
+    for (source_path, _) in &refined_yaml_hits {
         // Only add if we don't already have it
-        if !context.file_contents_map.contains_key(&final_yaml_target_path)
-            && !context.yaml_summaries.contains_key(&final_yaml_target_path)
+        if !context.file_contents_map.contains_key(source_path)
+            && !context.yaml_summaries.contains_key(source_path)
         {
-            match yaml_service.management.get_parsed_yaml_for_file_sync(project, &final_yaml_target_path, project_dir) {
+            match yaml_service.management.get_parsed_yaml_for_file_sync(project, source_path, project_dir) {
                 Ok(yaml_data) => {
-                    context.add_yaml_summary(final_yaml_target_path.clone(), yaml_data.description);
-                    context.add_thought(format!("Added new YAML summary from refined search: {}", final_yaml_target_path));
+                    context.add_yaml_summary(source_path.clone(), yaml_data.description);
+                    context.add_thought(format!("Added new YAML summary from refined search: {}", source_path));
                 }
                 Err(err) => {
                     context.add_thought(format!(
                         "Failed to parse YAML for refined BM25F result: {}. Error: {}",
-                        final_yaml_target_path, err
+                        source_path, err
                     ));
                 }
             }
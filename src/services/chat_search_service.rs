@@ -0,0 +1,144 @@
+// src/services/chat_search_service.rs
+use crate::models::Project;
+use crate::services::embedding_service::EmbeddingService;
+use crate::services::project_service::query_management::QueryManager;
+use crate::services::utils::text::truncate_chars;
+use crate::services::vector_store::synced_collection::{sync_collection, SyncDocument, SyncReport};
+use crate::services::vector_store::{self, payload_string};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Characters of a message that are embedded and kept as its snippet.
+const MAX_MESSAGE_CHARS: usize = 6_000;
+
+/// Searches re-sync a project's chat index at most this often, since a sync reads every
+/// query file. Messages saved in between are found once the next sync runs.
+const SEARCH_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// When each project's chat index (by project dir) was last synced in this process.
+static LAST_SYNCS: OnceLock<Mutex<HashMap<PathBuf, Instant>>> = OnceLock::new();
+
+fn last_syncs() -> &'static Mutex<HashMap<PathBuf, Instant>> {
+    LAST_SYNCS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Name of the collection holding a project's chat message embeddings.
+pub fn chat_collection_name(project: &Project) -> String {
+    format!("project_{}_chats", project.name)
+}
+
+/// A past chat message that matched a search.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatSearchHit {
+    pub query_id: String,
+    pub query_title: String,
+    pub message_id: String,
+    pub role: String,
+    pub timestamp: Option<String>,
+    pub snippet: String,
+    pub score: f32,
+}
+
+pub struct ChatSearchService;
+
+impl ChatSearchService {
+    pub fn new() -> Self {
+        ChatSearchService
+    }
+
+    /// Every visible, non-empty user/model message in `queries/*.json`, keyed by message id.
+    fn collect_messages(&self, project_dir: &Path) -> Vec<SyncDocument> {
+        let query_manager = QueryManager::new();
+        let query_ids = query_manager.get_query_filenames(project_dir).unwrap_or_default();
+
+        let mut documents = Vec::new();
+        for (query_id, query_title) in query_ids {
+            let Ok(query_data) = query_manager.load_query_data(project_dir, &query_id) else {
+                continue;
+            };
+            for message in query_data.chat_nodes.values() {
+                if message.hidden || message.content.trim().is_empty() || !matches!(message.role.as_str(), "user" | "model") {
+                    continue;
+                }
+                let text = truncate_chars(&message.content, MAX_MESSAGE_CHARS).to_string();
+                let mut payload = HashMap::new();
+                payload.insert("query_id".to_string(), Value::from(query_id.as_str()));
+                payload.insert("query_title".to_string(), Value::from(query_title.as_str()));
+                payload.insert("message_id".to_string(), Value::from(message.id.to_string()));
+                payload.insert("role".to_string(), Value::from(message.role.as_str()));
+                if let Some(timestamp) = message.timestamp {
+                    payload.insert("timestamp".to_string(), Value::from(timestamp.to_rfc3339()));
+                }
+                payload.insert("content".to_string(), Value::from(text.as_str()));
+                documents.push(SyncDocument { key: message.id.to_string(), text, payload });
+            }
+        }
+        documents
+    }
+
+    /// Brings the chats collection up to date with the query files: new and edited messages
+    /// are embedded, deleted or hidden ones removed.
+    pub async fn sync_index(&self, project: &Project, project_dir: &Path) -> Result<SyncReport, String> {
+        let documents = self.collect_messages(project_dir);
+        let report = sync_collection(project, project_dir, &chat_collection_name(project), "chat_index.json", documents).await?;
+        last_syncs().lock().unwrap().insert(project_dir.to_path_buf(), Instant::now());
+        Ok(report)
+    }
+
+    /// Past chat messages most similar to `query`, across all of the project's queries except
+    /// `exclude_query_id`. The index is synced first unless that happened within
+    /// `SEARCH_SYNC_INTERVAL`.
+    pub async fn search(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        query: &str,
+        limit: usize,
+        exclude_query_id: Option<&str>,
+    ) -> Result<Vec<ChatSearchHit>, String> {
+        let synced_recently = last_syncs()
+            .lock()
+            .unwrap()
+            .get(project_dir)
+            .is_some_and(|synced_at| synced_at.elapsed() < SEARCH_SYNC_INTERVAL);
+        if !synced_recently {
+            self.sync_index(project, project_dir).await?;
+        }
+
+        let query_embedding = EmbeddingService::for_project(project_dir)
+            .generate_embedding(query, &project.embedding_config)
+            .await
+            .map_err(|e| e.to_string())?;
+        let vector_store = vector_store::open_project_store(project, project_dir)
+            .await
+            .map_err(|e| format!("Failed to open vector store: {}", e))?;
+        // The excluded query's own messages would otherwise crowd out the rest
+        let oversample = if exclude_query_id.is_some() { 3 } else { 1 };
+        let points = vector_store
+            .search_points(&chat_collection_name(project), query_embedding, (limit * oversample) as u64, false, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(points
+            .into_iter()
+            .map(|point| {
+                let timestamp = payload_string(&point.payload, "timestamp");
+                ChatSearchHit {
+                    query_id: payload_string(&point.payload, "query_id"),
+                    query_title: payload_string(&point.payload, "query_title"),
+                    message_id: payload_string(&point.payload, "message_id"),
+                    role: payload_string(&point.payload, "role"),
+                    timestamp: if timestamp.is_empty() { None } else { Some(timestamp) },
+                    snippet: payload_string(&point.payload, "content"),
+                    score: point.score,
+                }
+            })
+            .filter(|hit| exclude_query_id != Some(hit.query_id.as_str()))
+            .take(limit)
+            .collect())
+    }
+}
@@ -93,7 +93,7 @@ pub fn kmeans_cosine(
 }
 
 /// Calculates cosine distance between two vectors (1 - cosine_similarity)
-fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
     let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
     let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
     let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -0,0 +1,187 @@
+// src/services/duplicate_service.rs
+use crate::models::Project;
+use crate::services::clustering_service::cosine_distance;
+use crate::services::file::FileService;
+use crate::services::llm_service::{LlmService, LlmServiceConfig};
+use crate::services::utils::text::truncate_chars;
+use crate::services::vector_store::{self, payload_string, project_collection_name};
+use crate::services::yaml::{load_file_yaml, FileYamlData};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Characters of each file sent to the LLM when asking for a consolidation plan.
+const MAX_SUGGESTION_FILE_CHARS: usize = 12_000;
+
+/// Two files whose whole-file embeddings are at least `threshold` cosine-similar.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    pub file_a: String,
+    pub file_b: String,
+    pub similarity: f32,
+    /// Function/method names defined in both files
+    pub shared_functions: Vec<String>,
+    /// Calls made from both files
+    pub shared_calls: Vec<String>,
+    pub consolidation: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateReport {
+    pub threshold: f32,
+    pub files_compared: usize,
+    pub pairs: Vec<DuplicatePair>,
+}
+
+/// Index pairs `(i, j, similarity)` with `i < j` and similarity >= `threshold`, most similar first.
+pub fn near_duplicate_pairs(vectors: &[Vec<f32>], threshold: f32) -> Vec<(usize, usize, f32)> {
+    let mut pairs = Vec::new();
+    for i in 0..vectors.len() {
+        for j in (i + 1)..vectors.len() {
+            let similarity = 1.0 - cosine_distance(&vectors[i], &vectors[j]);
+            if similarity >= threshold {
+                pairs.push((i, j, similarity));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    pairs
+}
+
+fn function_names(yaml: &FileYamlData) -> BTreeSet<String> {
+    let methods = yaml.classes.iter().flat_map(|class| class.methods.iter());
+    yaml.functions.iter().chain(methods).map(|function| function.name.clone()).collect()
+}
+
+fn calls(yaml: &FileYamlData) -> BTreeSet<String> {
+    let methods = yaml.classes.iter().flat_map(|class| class.methods.iter());
+    yaml.functions
+        .iter()
+        .chain(methods)
+        .flat_map(|function| function.calls.iter().cloned())
+        .collect()
+}
+
+/// Function names and calls that appear in both summaries.
+pub fn shared_symbols(a: &FileYamlData, b: &FileYamlData) -> (Vec<String>, Vec<String>) {
+    let shared_functions = function_names(a).intersection(&function_names(b)).cloned().collect();
+    let shared_calls = calls(a).intersection(&calls(b)).cloned().collect();
+    (shared_functions, shared_calls)
+}
+
+pub struct DuplicateService;
+
+impl DuplicateService {
+    pub fn new() -> Self {
+        DuplicateService
+    }
+
+    /// Compares every pair of stored file vectors and reports those at or above `threshold`.
+    /// When `suggest` is set, the top `max_pairs` pairs also get an LLM consolidation plan.
+    pub async fn find_near_duplicates(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        threshold: f32,
+        max_pairs: usize,
+        suggest: bool,
+    ) -> Result<DuplicateReport, String> {
+        let vector_store = vector_store::open_project_store(project, project_dir)
+            .await
+            .map_err(|e| format!("Failed to open vector store: {}", e))?;
+        let points = vector_store
+            .scroll_file_points(&project_collection_name(project))
+            .await
+            .map_err(|e| format!("Failed to retrieve embeddings: {}", e))?;
+
+        let (files, vectors): (Vec<String>, Vec<Vec<f32>>) = points
+            .into_iter()
+            .map(|point| (payload_string(&point.payload, "file_path"), point.vector))
+            .filter(|(file_path, _)| !file_path.is_empty())
+            .unzip();
+
+        let mut pairs = Vec::new();
+        for (i, j, similarity) in near_duplicate_pairs(&vectors, threshold).into_iter().take(max_pairs) {
+            let (shared_functions, shared_calls) =
+                match (load_file_yaml(project_dir, &files[i]), load_file_yaml(project_dir, &files[j])) {
+                    (Some(a), Some(b)) => shared_symbols(&a, &b),
+                    _ => (Vec::new(), Vec::new()),
+                };
+            pairs.push(DuplicatePair {
+                file_a: files[i].clone(),
+                file_b: files[j].clone(),
+                similarity,
+                shared_functions,
+                shared_calls,
+                consolidation: None,
+            });
+        }
+
+        if suggest {
+            for pair in pairs.iter_mut() {
+                pair.consolidation = Some(self.suggest_consolidation(project, pair).await);
+            }
+        }
+
+        Ok(DuplicateReport {
+            threshold,
+            files_compared: files.len(),
+            pairs,
+        })
+    }
+
+    async fn suggest_consolidation(&self, project: &Project, pair: &DuplicatePair) -> String {
+        let file_service = FileService {};
+        let content_a = file_service.read_specific_file(project, &pair.file_a).unwrap_or_default();
+        let content_b = file_service.read_specific_file(project, &pair.file_b).unwrap_or_default();
+
+        let prompt = format!(
+            "These two files look like near-duplicates (embedding cosine similarity {:.3}).\n\
+            Shared function names: {}\nShared calls: {}\n\n\
+            Explain briefly what is duplicated and suggest how to consolidate it into shared code \
+            (what to extract, where it should live, and how both call sites change). \
+            If the similarity is superficial and they should stay separate, say so.\n\n\
+            File: {}\n```\n{}\n```\n\nFile: {}\n```\n{}\n```\n",
+            pair.similarity,
+            pair.shared_functions.join(", "),
+            pair.shared_calls.join(", "),
+            pair.file_a,
+            truncate_chars(&content_a, MAX_SUGGESTION_FILE_CHARS),
+            pair.file_b,
+            truncate_chars(&content_b, MAX_SUGGESTION_FILE_CHARS),
+        );
+
+        LlmService::new()
+            .get_analysis(&prompt, &project.provider, project.specific_model.as_deref(), Some(LlmServiceConfig::new()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_pairs_above_threshold_most_similar_first() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.99, 0.1], vec![0.0, 1.0], vec![1.0, 0.0]];
+        let pairs = near_duplicate_pairs(&vectors, 0.95);
+        assert_eq!(pairs.len(), 3);
+        assert_eq!((pairs[0].0, pairs[0].1), (0, 3));
+        assert!(pairs.iter().all(|&(i, j, _)| i != 2 && j != 2));
+    }
+
+    #[test]
+    fn shared_symbols_include_methods_and_calls() {
+        let a: FileYamlData = serde_yaml::from_str(
+            "description: a\nfunctions:\n  - name: suggest_branch_name\n    calls: [LlmService::new, get_analysis]\n",
+        )
+        .unwrap();
+        let b: FileYamlData = serde_yaml::from_str(
+            "description: b\nclasses:\n  - name: Handler\n    methods:\n      - name: suggest_branch_name\n        calls: [get_analysis, format]\n",
+        )
+        .unwrap();
+        let (functions, calls) = shared_symbols(&a, &b);
+        assert_eq!(functions, vec!["suggest_branch_name"]);
+        assert_eq!(calls, vec!["get_analysis"]);
+    }
+}
@@ -0,0 +1,78 @@
+// src/services/embedding_cache.rs
+use crate::models::{EmbeddingConfig, EmbeddingProviderKind};
+use git2::{ObjectType, Oid};
+use std::path::{Path, PathBuf};
+
+/// On-disk cache of embeddings keyed by a hash of (model, dimensions, content), stored as
+/// one JSON file per entry under `output/<project>/embedding_cache/`. Identical text is
+/// only ever sent to the embedding API once per model.
+pub struct EmbeddingCache {
+    cache_dir: PathBuf,
+}
+
+impl EmbeddingCache {
+    pub fn new(project_dir: &Path) -> Self {
+        EmbeddingCache {
+            cache_dir: project_dir.join("embedding_cache"),
+        }
+    }
+
+    /// Content hash used as the cache key. The model and dimensions are part of the
+    /// hashed bytes so switching models never returns a stale vector; other providers
+    /// also hash the provider and endpoint (OpenAI keeps the original key format).
+    pub fn cache_key(content: &str, config: &EmbeddingConfig) -> String {
+        let keyed = match config.provider {
+            EmbeddingProviderKind::OpenAi => format!("{}\n{}\n{}", config.model, config.dimensions, content),
+            provider => format!(
+                "{:?}\n{}\n{}\n{}\n{}",
+                provider,
+                config.base_url.as_deref().unwrap_or(""),
+                config.model,
+                config.dimensions,
+                content
+            ),
+        };
+        Oid::hash_object(ObjectType::Blob, keyed.as_bytes())
+            .map(|oid| oid.to_string())
+            .unwrap_or_default()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.json", key))
+    }
+
+    pub fn get(&self, content: &str, config: &EmbeddingConfig) -> Option<Vec<f32>> {
+        let key = Self::cache_key(content, config);
+        if key.is_empty() {
+            return None;
+        }
+        let json = std::fs::read_to_string(self.entry_path(&key)).ok()?;
+        let embedding: Vec<f32> = serde_json::from_str(&json).ok()?;
+        // Guard against entries written by a misbehaving model
+        if embedding.len() == config.dimensions as usize {
+            Some(embedding)
+        } else {
+            None
+        }
+    }
+
+    pub fn insert(&self, content: &str, config: &EmbeddingConfig, embedding: &[f32]) {
+        let key = Self::cache_key(content, config);
+        if key.is_empty() {
+            return;
+        }
+        let result = std::fs::create_dir_all(&self.cache_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string(embedding).map_err(|e| e.to_string()))
+            .and_then(|json| {
+                // Write-then-rename so a concurrent reader never sees a partial entry
+                let tmp_path = self.cache_dir.join(format!("{}.json.tmp", key));
+                std::fs::write(&tmp_path, json)
+                    .and_then(|_| std::fs::rename(&tmp_path, self.entry_path(&key)))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            eprintln!("Failed to write embedding cache entry: {}", e);
+        }
+    }
+}
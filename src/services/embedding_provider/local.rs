@@ -0,0 +1,78 @@
+// src/services/embedding_provider/local.rs
+use super::{EmbeddingProvider, EmbeddingResult};
+use crate::services::tokenizer::tokenize;
+use async_trait::async_trait;
+
+/// Deterministic bag-of-terms embedder: each token from the code-aware tokenizer is hashed
+/// into one of `dimensions` buckets with a hashed sign, weighted by log term frequency and
+/// L2-normalized. No network and no model, so it works air-gapped and in tests, and the same
+/// text always gives the same vector.
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        HashingEmbedder { dimensions: dimensions.max(1) }
+    }
+
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut counts = vec![0.0f32; self.dimensions];
+        for token in tokenize(text) {
+            let hash = fnv1a(token.as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+            counts[bucket] += sign;
+        }
+
+        let mut vector: Vec<f32> = counts.into_iter().map(|c| c.signum() * (1.0 + c.abs()).ln()).collect();
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+        vector
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashingEmbedder {
+    async fn embed_batch(&self, inputs: &[&str]) -> EmbeddingResult<Vec<Vec<f32>>> {
+        Ok(inputs.iter().map(|input| self.embed_text(input)).collect())
+    }
+
+    async fn embed(&self, input: &str) -> EmbeddingResult<Vec<f32>> {
+        Ok(self.embed_text(input))
+    }
+
+    /// Cheaper to recompute than to read back from disk.
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::clustering_service::cosine_distance;
+
+    #[test]
+    fn same_text_same_vector() {
+        let embedder = HashingEmbedder::new(64);
+        assert_eq!(embedder.embed_text("parse yaml files"), embedder.embed_text("parse yaml files"));
+        assert_eq!(embedder.embed_text("parse yaml files").len(), 64);
+    }
+
+    #[test]
+    fn shared_terms_are_closer() {
+        let embedder = HashingEmbedder::new(256);
+        let query = embedder.embed_text("generate branch name");
+        let near = embedder.embed_text("suggest_branch_name generates a git branch name");
+        let far = embedder.embed_text("cosine clustering of vectors");
+        assert!(cosine_distance(&query, &near) < cosine_distance(&query, &far));
+    }
+}
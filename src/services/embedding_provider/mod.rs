@@ -0,0 +1,40 @@
+// src/services/embedding_provider/mod.rs
+pub mod local;
+pub mod openai;
+
+use crate::models::{EmbeddingConfig, EmbeddingProviderKind};
+use async_trait::async_trait;
+use local::HashingEmbedder;
+use openai::OpenAiEmbeddings;
+use std::error::Error;
+
+pub type EmbeddingResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Something that turns text into vectors of the configured dimension.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embeds every input; the result is in the same order as `inputs`.
+    async fn embed_batch(&self, inputs: &[&str]) -> EmbeddingResult<Vec<Vec<f32>>>;
+
+    async fn embed(&self, input: &str) -> EmbeddingResult<Vec<f32>> {
+        self.embed_batch(&[input])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Embedding response contained no data".into())
+    }
+
+    /// Whether vectors are worth keeping in the on-disk embedding cache.
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// Provider selected by a project's embedding config.
+pub fn provider_for(config: &EmbeddingConfig) -> EmbeddingResult<Box<dyn EmbeddingProvider>> {
+    match config.provider {
+        EmbeddingProviderKind::OpenAi => Ok(Box::new(OpenAiEmbeddings::openai(config))),
+        EmbeddingProviderKind::OpenAiCompatible => Ok(Box::new(OpenAiEmbeddings::compatible(config)?)),
+        EmbeddingProviderKind::LocalHashing => Ok(Box::new(HashingEmbedder::new(config.dimensions as usize))),
+    }
+}
@@ -0,0 +1,115 @@
+// src/services/embedding_provider/openai.rs
+use super::{EmbeddingProvider, EmbeddingResult};
+use crate::models::{EmbeddingConfig, DEFAULT_EMBEDDING_MODEL};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::env;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// OpenAI `/embeddings` API, or any server that implements the same protocol.
+pub struct OpenAiEmbeddings {
+    config: EmbeddingConfig,
+    embeddings_url: String,
+    /// Only OpenAI itself: compatible servers often reject the `dimensions` parameter and
+    /// don't use OpenAI credentials.
+    is_openai: bool,
+}
+
+impl OpenAiEmbeddings {
+    pub fn openai(config: &EmbeddingConfig) -> Self {
+        OpenAiEmbeddings {
+            config: config.clone(),
+            embeddings_url: format!("{}/embeddings", OPENAI_BASE_URL),
+            is_openai: true,
+        }
+    }
+
+    /// Server at `config.base_url`; sends `OPENAI_COMPATIBLE_API_KEY` as a bearer token if set.
+    pub fn compatible(config: &EmbeddingConfig) -> EmbeddingResult<Self> {
+        let base_url = config
+            .base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .ok_or("An OpenAI-compatible embedding provider needs a base URL")?;
+        Ok(OpenAiEmbeddings {
+            config: config.clone(),
+            embeddings_url: format!("{}/embeddings", base_url.trim_end_matches('/')),
+            is_openai: false,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddings {
+    async fn embed(&self, input: &str) -> EmbeddingResult<Vec<f32>> {
+        if self.is_openai && self.config.model == DEFAULT_EMBEDDING_MODEL {
+            // Use the correct function from the llm_api_access crate
+            return llm_api_access::openai::get_embedding(input.to_string(), Some(self.config.dimensions)).await;
+        }
+        // llm_api_access only knows the default model, so everything else is called directly
+        self.embed_batch(&[input])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Embedding response contained no data".into())
+    }
+
+    async fn embed_batch(&self, inputs: &[&str]) -> EmbeddingResult<Vec<Vec<f32>>> {
+        dotenv::dotenv().ok();
+        let mut body = json!({
+            "model": self.config.model,
+            "input": inputs,
+            "encoding_format": "float",
+        });
+        let mut request = reqwest::Client::new().post(&self.embeddings_url);
+        if self.is_openai {
+            body["dimensions"] = json!(self.config.dimensions);
+            request = request.bearer_auth(env::var("OPEN_AI_KEY")?);
+            if let Ok(org) = env::var("OPEN_AI_ORG") {
+                request = request.header("OpenAI-Organization", org);
+            }
+        } else if let Ok(api_key) = env::var("OPENAI_COMPATIBLE_API_KEY") {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.json(&body).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Embedding request failed ({}): {}", status, body).into());
+        }
+
+        let mut parsed: EmbeddingResponse = response.json().await?;
+        if parsed.data.len() != inputs.len() {
+            return Err(format!("Expected {} embeddings, got {}", inputs.len(), parsed.data.len()).into());
+        }
+        parsed.data.sort_by_key(|d| d.index);
+        let embeddings: Vec<Vec<f32>> = parsed.data.into_iter().map(|d| d.embedding).collect();
+
+        if let Some(wrong) = embeddings.iter().find(|e| e.len() != self.config.dimensions as usize) {
+            return Err(format!(
+                "{} returned {}-dimensional embeddings but the project is configured for {}",
+                self.config.model,
+                wrong.len(),
+                self.config.dimensions
+            )
+            .into());
+        }
+        Ok(embeddings)
+    }
+}
@@ -1,19 +1,69 @@
 // src/services/embedding_service.rs
+use crate::models::EmbeddingConfig;
+use crate::services::embedding_cache::EmbeddingCache;
+use crate::services::embedding_provider::provider_for;
 use std::error::Error;
+use std::path::Path;
 
-pub struct EmbeddingService {}
+/// Maximum number of texts sent in one embeddings request.
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+pub struct EmbeddingService {
+    cache: EmbeddingCache,
+}
 
 impl EmbeddingService {
-    pub fn new() -> Self {
-        EmbeddingService {}
+    /// Embedding service that reuses vectors cached under the project's output dir.
+    pub fn for_project(project_dir: &Path) -> Self {
+        EmbeddingService {
+            cache: EmbeddingCache::new(project_dir),
+        }
     }
-    
+
+    /// Embeds `content` with the provider selected in `config`.
     pub async fn generate_embedding(
-        &self, 
-        content: &str, 
-        dimensions: Option<u32>
+        &self,
+        content: &str,
+        config: &EmbeddingConfig,
     ) -> Result<Vec<f32>, Box<dyn Error + Send + Sync>> {
-        // Use the correct function from the llm_api_access crate
-        llm_api_access::openai::get_embedding(content.to_string(), dimensions).await
+        let provider = provider_for(config)?;
+        if !provider.cacheable() {
+            return provider.embed(content).await;
+        }
+        if let Some(embedding) = self.cache.get(content, config) {
+            return Ok(embedding);
+        }
+
+        let embedding = provider.embed(content).await?;
+        self.cache.insert(content, config, &embedding);
+        Ok(embedding)
+    }
+
+    /// Embeds many texts at once. Cached texts are served from disk and the rest are sent
+    /// in batches of `EMBEDDING_BATCH_SIZE`; the result is in the same order as `contents`.
+    pub async fn generate_embeddings(
+        &self,
+        contents: &[String],
+        config: &EmbeddingConfig,
+    ) -> Result<Vec<Vec<f32>>, Box<dyn Error + Send + Sync>> {
+        let provider = provider_for(config)?;
+        let mut embeddings: Vec<Option<Vec<f32>>> = contents
+            .iter()
+            .map(|content| provider.cacheable().then(|| self.cache.get(content, config)).flatten())
+            .collect();
+
+        let uncached: Vec<usize> = (0..contents.len()).filter(|&i| embeddings[i].is_none()).collect();
+        for batch in uncached.chunks(EMBEDDING_BATCH_SIZE) {
+            let inputs: Vec<&str> = batch.iter().map(|&i| contents[i].as_str()).collect();
+            let batch_embeddings = provider.embed_batch(&inputs).await?;
+            for (&i, embedding) in batch.iter().zip(batch_embeddings) {
+                if provider.cacheable() {
+                    self.cache.insert(&contents[i], config, &embedding);
+                }
+                embeddings[i] = Some(embedding);
+            }
+        }
+
+        Ok(embeddings.into_iter().map(|e| e.unwrap_or_default()).collect())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,415 @@
+// src/services/job_service/mod.rs
+pub mod runner;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use uuid::Uuid;
+
+/// Finished jobs kept on disk per project; older ones are pruned when new jobs are queued.
+const MAX_FINISHED_JOBS_PER_PROJECT: usize = 50;
+
+/// A job together with the file it is persisted to.
+struct JobEntry {
+    job: Job,
+    path: PathBuf,
+}
+
+/// Every known job (loaded from disk or created in this process), keyed by job id.
+static JOBS: OnceLock<Mutex<HashMap<String, JobEntry>>> = OnceLock::new();
+/// One lock per project so that a project's jobs run one after another.
+static PROJECT_LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+
+fn jobs() -> &'static Mutex<HashMap<String, JobEntry>> {
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn project_lock(project_name: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let locks = PROJECT_LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    locks
+        .lock()
+        .unwrap()
+        .entry(project_name.to_string())
+        .or_default()
+        .clone()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    /// Generate YAML and embeddings for every file that needs it
+    Generate { force: bool },
+    /// Bring one file's YAML (if used) and embedding up to date
+    RegenerateFile { file_path: String },
+    /// Re-embed every file without touching the YAML
+    ReembedAll,
+    /// Compare the vector store with the project's embedding metadata
+    Reconcile,
+    /// Re-embed every file into the pending embedding config's collection, then switch to it
+    MigrateEmbeddings,
+}
+
+impl JobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobKind::Generate { .. } => "generate",
+            JobKind::RegenerateFile { .. } => "regenerate_file",
+            JobKind::ReembedAll => "reembed_all",
+            JobKind::Reconcile => "reconcile",
+            JobKind::MigrateEmbeddings => "migrate_embeddings",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStage {
+    Pending,
+    GeneratingYaml,
+    Embedding,
+    Done,
+    Skipped, // Already up to date
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileProgress {
+    pub path: String,
+    pub stage: FileStage,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobLogEntry {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Job {
+    pub id: String,
+    pub project: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub files: Vec<FileProgress>,
+    #[serde(default)]
+    pub log: Vec<JobLogEntry>,
+    #[serde(default)]
+    pub cancel_requested: bool,
+    pub queued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+/// Counts derived from a job's file list, plus a naive ETA from the average time per file so far.
+#[derive(Serialize, Debug, Clone)]
+pub struct JobProgress {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub remaining: usize,
+    pub eta_seconds: Option<i64>,
+}
+
+impl Job {
+    pub fn progress(&self) -> JobProgress {
+        let count = |stage: FileStage| self.files.iter().filter(|f| f.stage == stage).count();
+        let done = count(FileStage::Done);
+        let failed = count(FileStage::Failed);
+        let skipped = count(FileStage::Skipped);
+        let remaining = self.files.len() - done - failed - skipped;
+
+        let processed = done + failed;
+        let eta_seconds = match self.started_at {
+            Some(started_at) if self.status == JobStatus::Running && processed > 0 => {
+                let elapsed = (Utc::now() - started_at).num_seconds();
+                Some(elapsed * remaining as i64 / processed as i64)
+            }
+            _ => None,
+        };
+
+        JobProgress {
+            total: self.files.len(),
+            done,
+            failed,
+            skipped,
+            remaining,
+            eta_seconds,
+        }
+    }
+}
+
+/// Directory holding a project's job records: `output/<project>/jobs/`.
+fn jobs_dir(output_dir: &str, project_name: &str) -> PathBuf {
+    Path::new(output_dir).join(project_name).join("jobs")
+}
+
+fn persist(entry: &JobEntry) {
+    let result = entry
+        .path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&entry.job).map_err(|e| e.to_string()))
+        .and_then(|json| {
+            let tmp_path = entry.path.with_extension("json.tmp");
+            std::fs::write(&tmp_path, json)
+                .and_then(|_| std::fs::rename(&tmp_path, &entry.path))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to persist job {}: {}", entry.job.id, e);
+    }
+}
+
+pub struct JobService;
+
+impl JobService {
+    pub fn new() -> Self {
+        JobService
+    }
+
+    /// Records a queued job on disk and starts it once the project's earlier jobs are done.
+    pub fn enqueue(&self, output_dir: &str, project_name: &str, kind: JobKind) -> String {
+        let job = Job {
+            id: Uuid::new_v4().to_string(),
+            project: project_name.to_string(),
+            kind,
+            status: JobStatus::Queued,
+            files: Vec::new(),
+            log: Vec::new(),
+            cancel_requested: false,
+            queued_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+        };
+        let id = job.id.clone();
+        let entry = JobEntry {
+            path: jobs_dir(output_dir, project_name).join(format!("{}.json", id)),
+            job,
+        };
+        persist(&entry);
+        jobs().lock().unwrap().insert(id.clone(), entry);
+
+        self.prune_finished_jobs(project_name);
+        self.spawn(id.clone(), output_dir.to_string());
+        id
+    }
+
+    /// Queues `kind` unless the same kind of job is already queued or running for the project.
+    pub fn enqueue_unique(&self, output_dir: &str, project_name: &str, kind: JobKind) -> Option<String> {
+        let already_active = jobs().lock().unwrap().values().any(|entry| {
+            entry.job.project == project_name && entry.job.kind == kind && !entry.job.status.is_finished()
+        });
+        if already_active {
+            None
+        } else {
+            Some(self.enqueue(output_dir, project_name, kind))
+        }
+    }
+
+    fn spawn(&self, job_id: String, output_dir: String) {
+        tokio::spawn(async move {
+            let job_service = JobService::new();
+            let Some(job) = job_service.get_job(&job_id) else {
+                return;
+            };
+
+            // Jobs for the same project run one at a time, in the order they were queued
+            let lock = project_lock(&job.project);
+            let _guard = lock.lock().await;
+
+            if job_service.is_cancelled(&job_id) {
+                job_service.finish_job(&job_id, Err("Cancelled before it started".to_string()));
+                return;
+            }
+            job_service.update(&job_id, |job| {
+                job.status = JobStatus::Running;
+                job.started_at = Some(Utc::now());
+            });
+            job_service.log(&job_id, &format!("Started {} job", job.kind.name()));
+
+            let result = runner::run_job(&job, &output_dir).await;
+            job_service.finish_job(&job_id, result);
+        });
+    }
+
+    /// Loads job records from disk and re-queues any that were queued or running when the
+    /// server stopped. Called once at startup.
+    pub fn resume_jobs(&self, output_dir: &str) {
+        let Ok(project_dirs) = std::fs::read_dir(output_dir) else {
+            return;
+        };
+
+        let mut to_resume: Vec<(DateTime<Utc>, String)> = Vec::new();
+        for project_dir in project_dirs.filter_map(Result::ok) {
+            let Ok(job_files) = std::fs::read_dir(project_dir.path().join("jobs")) else {
+                continue;
+            };
+            for job_file in job_files.filter_map(Result::ok) {
+                let path = job_file.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let Ok(json) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                match serde_json::from_str::<Job>(&json) {
+                    Ok(job) => {
+                        if !job.status.is_finished() {
+                            to_resume.push((job.queued_at, job.id.clone()));
+                        }
+                        jobs().lock().unwrap().insert(job.id.clone(), JobEntry { job, path });
+                    }
+                    Err(e) => eprintln!("Skipping unreadable job file {:?}: {}", path, e),
+                }
+            }
+        }
+
+        to_resume.sort();
+        for (_, job_id) in to_resume {
+            self.update(&job_id, |job| {
+                job.status = JobStatus::Queued;
+                job.started_at = None;
+            });
+            self.log(&job_id, "Re-queued after server restart");
+            self.spawn(job_id, output_dir.to_string());
+        }
+    }
+
+    /// Applies `change` to the job and writes it back to disk.
+    fn update(&self, job_id: &str, change: impl FnOnce(&mut Job)) {
+        let mut jobs = jobs().lock().unwrap();
+        if let Some(entry) = jobs.get_mut(job_id) {
+            change(&mut entry.job);
+            persist(entry);
+        }
+    }
+
+    pub fn log(&self, job_id: &str, message: &str) {
+        println!("[job {}] {}", job_id, message);
+        self.update(job_id, |job| {
+            job.log.push(JobLogEntry {
+                at: Utc::now(),
+                message: message.to_string(),
+            })
+        });
+    }
+
+    pub fn set_files(&self, job_id: &str, files: Vec<FileProgress>) {
+        self.update(job_id, |job| job.files = files);
+    }
+
+    pub fn update_file(&self, job_id: &str, path: &str, stage: FileStage, error: Option<String>) {
+        if let Some(error) = &error {
+            self.log(job_id, &format!("{}: {}", path, error));
+        }
+        self.update(job_id, |job| {
+            if let Some(file) = job.files.iter_mut().find(|f| f.path == path) {
+                file.stage = stage;
+                file.error = error;
+            }
+        });
+    }
+
+    /// Marks the job finished. A job whose cancellation was requested ends up `Cancelled`
+    /// regardless of how far it got.
+    pub fn finish_job(&self, job_id: &str, result: Result<(), String>) {
+        self.update(job_id, |job| {
+            job.status = match (&result, job.cancel_requested) {
+                (_, true) => JobStatus::Cancelled,
+                (Ok(_), false) => JobStatus::Completed,
+                (Err(_), false) => JobStatus::Failed,
+            };
+            job.error = result.err();
+            job.finished_at = Some(Utc::now());
+            job.log.push(JobLogEntry {
+                at: Utc::now(),
+                message: format!("Finished: {:?}", job.status),
+            });
+        });
+    }
+
+    /// Requests cancellation. Queued jobs never start; running jobs stop at their next check.
+    pub fn cancel_job(&self, job_id: &str) -> Result<Job, String> {
+        let job = self.get_job(job_id).ok_or_else(|| format!("Job {} not found", job_id))?;
+        if job.status.is_finished() {
+            return Err(format!("Job {} has already finished", job_id));
+        }
+        self.update(job_id, |job| job.cancel_requested = true);
+        self.log(job_id, "Cancellation requested");
+        self.get_job(job_id).ok_or_else(|| format!("Job {} not found", job_id))
+    }
+
+    pub fn is_cancelled(&self, job_id: &str) -> bool {
+        jobs()
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .map(|entry| entry.job.cancel_requested)
+            .unwrap_or(false)
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Option<Job> {
+        jobs().lock().unwrap().get(job_id).map(|entry| entry.job.clone())
+    }
+
+    /// Jobs, newest first, optionally limited to one project.
+    pub fn list_jobs(&self, project_name: Option<&str>) -> Vec<Job> {
+        let mut list: Vec<Job> = jobs()
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| project_name.is_none_or(|name| entry.job.project == name))
+            .map(|entry| entry.job.clone())
+            .collect();
+        list.sort_by_key(|job| std::cmp::Reverse(job.queued_at));
+        list
+    }
+
+    /// Most recently queued job of the given kind (by `JobKind::name`) for a project.
+    pub fn latest_job_for_project(&self, project_name: &str, kind_name: &str) -> Option<Job> {
+        self.list_jobs(Some(project_name))
+            .into_iter()
+            .find(|job| job.kind.name() == kind_name)
+    }
+
+    fn prune_finished_jobs(&self, project_name: &str) {
+        let finished: Vec<Job> = self
+            .list_jobs(Some(project_name))
+            .into_iter()
+            .filter(|job| job.status.is_finished())
+            .skip(MAX_FINISHED_JOBS_PER_PROJECT)
+            .collect();
+
+        let mut jobs = jobs().lock().unwrap();
+        for job in finished {
+            if let Some(entry) = jobs.remove(&job.id) {
+                let _ = std::fs::remove_file(&entry.path);
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// src/services/job_service/runner.rs
+use crate::models::{Project, ProjectFile};
+use crate::services::git_service::{GitError, GitService};
+use crate::services::job_service::{FileProgress, FileStage, Job, JobKind, JobService};
+use crate::services::llm_service::LlmServiceConfig;
+use crate::services::project_service::ProjectService;
+use crate::services::yaml::update_search_indexes;
+use crate::services::yaml::management::{generation, migration, reconcile, YamlManagement};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Number of re-embedded files between metadata saves in a `ReembedAll` job.
+const REEMBED_SAVE_EVERY: usize = 16;
+
+/// Executes one job against the saved project. Returning `Err` marks the job failed.
+pub async fn run_job(job: &Job, output_dir: &str) -> Result<(), String> {
+    let project_dir = Path::new(output_dir).join(&job.project);
+    let yaml_management = YamlManagement::new();
+
+    match &job.kind {
+        JobKind::Generate { force } => {
+            let mut project = ProjectService::new().load_project(&project_dir)?;
+            generation::generate_yaml_files(&yaml_management, &mut project, output_dir, *force, &job.id).await
+        }
+        JobKind::RegenerateFile { file_path } => {
+            let mut project = ProjectService::new().load_project(&project_dir)?;
+            regenerate_file(&yaml_management, &mut project, file_path, output_dir, &job.id).await
+        }
+        JobKind::ReembedAll => {
+            let mut project = ProjectService::new().load_project(&project_dir)?;
+            reembed_all(&yaml_management, &mut project, output_dir, &job.id).await
+        }
+        JobKind::Reconcile => {
+            let report = reconcile::reconcile_saved_project(&job.project, output_dir).await?;
+            JobService::new().log(
+                &job.id,
+                &format!(
+                    "{} missing, {} re-embedded, {} failed, {} orphan points deleted",
+                    report.missing_files.len(),
+                    report.re_embedded.len(),
+                    report.re_embed_failed.len(),
+                    report.deleted_orphans
+                ),
+            );
+            Ok(())
+        }
+        JobKind::MigrateEmbeddings => migration::run_migration_job(&job.project, output_dir, &job.id).await,
+    }
+}
+
+/// Regenerates the file's YAML when it uses YAML and the YAML is missing or out of date,
+/// then replaces its embedding.
+async fn regenerate_file(
+    yaml_management: &YamlManagement,
+    project: &mut Project,
+    file_path: &str,
+    output_dir: &str,
+    job_id: &str,
+) -> Result<(), String> {
+    let job_service = JobService::new();
+    let project_dir = Path::new(output_dir).join(&project.name);
+    job_service.set_files(job_id, vec![FileProgress { path: file_path.to_string(), stage: FileStage::Pending, error: None }]);
+
+    let source_path = Path::new(file_path);
+    let yaml_path = project_dir.join(format!("{}.yml", file_path.replace("/", "*")));
+    let is_markdown = source_path.extension().and_then(|ext| ext.to_str()) == Some("md");
+    let use_yaml = !is_markdown
+        && project.file_yaml_override.get(file_path).copied().unwrap_or(project.default_use_yaml);
+
+    let needs_yaml = use_yaml && {
+        let repo_result = if project.git_integration_enabled {
+            GitService::open_repository(Path::new(&project.source_dir))
+        } else {
+            Err(GitError::Other("Git integration not enabled".to_string()))
+        };
+        yaml_management.file_service.needs_yaml_update(project, &repo_result, source_path, &yaml_path)
+    };
+
+    if needs_yaml {
+        job_service.update_file(job_id, file_path, FileStage::GeneratingYaml, None);
+        let content = std::fs::read_to_string(source_path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+        let project_file = ProjectFile {
+            path: file_path.to_string(),
+            content,
+            last_modified: 0,
+        };
+        let combined_content = yaml_management
+            .update_yaml_with_imports(project, &project_dir, &project_file, Some(LlmServiceConfig::new()), true)
+            .await
+            .ok_or_else(|| format!("YAML generation failed for {}", file_path))?;
+        std::fs::write(&yaml_path, &combined_content).map_err(|e| format!("Failed to write YAML: {}", e))?;
+        update_search_indexes(&project_dir, file_path, &combined_content);
+    }
+
+    if job_service.is_cancelled(job_id) {
+        return Err("Cancelled".to_string());
+    }
+
+    job_service.update_file(job_id, file_path, FileStage::Embedding, None);
+    let updates = reembed(yaml_management, project, &[file_path.to_string()], output_dir).await;
+    if updates.is_empty() {
+        let error = format!("Failed to embed {}", file_path);
+        job_service.update_file(job_id, file_path, FileStage::Failed, Some(error.clone()));
+        return Err(error);
+    }
+    ProjectService::new().save_embedding_updates(&project_dir, updates)?;
+    job_service.update_file(job_id, file_path, FileStage::Done, None);
+    Ok(())
+}
+
+/// Re-embeds every tracked file, e.g. after the YAML default or vector backend changed.
+/// Progress is saved every `REEMBED_SAVE_EVERY` files so a cancel or restart keeps it.
+async fn reembed_all(
+    yaml_management: &YamlManagement,
+    project: &mut Project,
+    output_dir: &str,
+    job_id: &str,
+) -> Result<(), String> {
+    let job_service = JobService::new();
+    let project_dir = Path::new(output_dir).join(&project.name);
+    let project_service = ProjectService::new();
+
+    let mut file_paths: Vec<String> = project.embeddings.keys().cloned().collect();
+    file_paths.sort();
+    job_service.set_files(
+        job_id,
+        file_paths
+            .iter()
+            .map(|path| FileProgress { path: path.clone(), stage: FileStage::Pending, error: None })
+            .collect(),
+    );
+
+    for chunk in file_paths.chunks(REEMBED_SAVE_EVERY) {
+        if job_service.is_cancelled(job_id) {
+            return Err("Cancelled".to_string());
+        }
+        for path in chunk {
+            job_service.update_file(job_id, path, FileStage::Embedding, None);
+        }
+
+        let updates = reembed(yaml_management, project, chunk, output_dir).await;
+        for path in chunk {
+            if updates.contains_key(path) {
+                job_service.update_file(job_id, path, FileStage::Done, None);
+            } else {
+                job_service.update_file(job_id, path, FileStage::Failed, Some("Re-embedding failed".to_string()));
+            }
+        }
+        project_service.save_embedding_updates(&project_dir, updates)?;
+    }
+    Ok(())
+}
+
+/// Re-embeds `file_paths` and returns the metadata of the ones that got a new point.
+async fn reembed(
+    yaml_management: &YamlManagement,
+    project: &mut Project,
+    file_paths: &[String],
+    output_dir: &str,
+) -> HashMap<String, crate::models::EmbeddingMetadata> {
+    let mut updates = HashMap::new();
+    for file_path in file_paths {
+        let previous_vector_id = project.embeddings.get(file_path).map(|m| m.vector_id.clone());
+        yaml_management.regenerate_embedding(project, file_path, output_dir).await;
+        // regenerate_embedding only touches the metadata when the new point was stored
+        if let Some(metadata) = project.embeddings.get(file_path) {
+            if Some(&metadata.vector_id) != previous_vector_id.as_ref() {
+                updates.insert(file_path.clone(), metadata.clone());
+            }
+        }
+    }
+    updates
+}
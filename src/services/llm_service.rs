@@ -9,6 +9,9 @@ use std::io::Error;
 use llm_api_access::config::LlmConfig;
 use serde_yaml; // For YAML deserialization
 use crate::services::yaml::FileYamlData; // Import the FileYamlData struct
+use crate::services::yaml::processing::chunking::{merge_chunk_yaml, split_source, SourceChunk, DEFAULT_MAX_CHUNK_CHARS};
+use crate::services::yaml::processing::line_ranges::verify_line_ranges;
+use crate::services::yaml::processing::rust_skeleton::{apply_annotations, rust_skeleton, skeleton_within};
 
 #[derive(Debug, Clone, Default)]
 pub struct LlmServiceConfig {
@@ -312,28 +315,170 @@ impl LlmService {
     /// Converts a ProjectFile's content into YAML format, with retry mechanism on parsing failure.
     /// It communicates parsing errors back to the LLM to facilitate correction.
     /// Returns raw, unescaped YAML string on success, or an error string if all attempts fail.
+    /// For Rust files the structure is parsed locally and the LLM only adds descriptions and
+    /// `calls` to that skeleton; names, types and line ranges in the result are always the
+    /// parsed ones. Line ranges the LLM gives for other files are checked against the source.
+    /// Files over `YAML_CHUNK_CHARS` are converted in chunks split at item boundaries, merged,
+    /// and given a file description written from the chunks' descriptions.
     pub async fn convert_to_yaml(&self, file: &ProjectFile, provider: &str, chat_model: Option<&str>, yaml_model: Option<&str>, config: Option<LlmServiceConfig>) -> Result<String, String> {
-        let max_attempts: u8 = 3; // Define maximum retry attempts
         let model_to_use = yaml_model.or(chat_model);
 
-        let user_prompt_template_path = Path::new("src/prompts/user.txt");
-        let struct_source_path = Path::new("src/services/yaml/mod.rs");
-        let model_prompt_path = Path::new("src/prompts/model.txt");
+        let skeleton = if Path::new(&file.path).extension().and_then(|ext| ext.to_str()) == Some("rs") {
+            match rust_skeleton(&file.content) {
+                Ok(skeleton) => Some(skeleton),
+                Err(e) => {
+                    eprintln!("No Rust skeleton for '{}', using the full YAML prompt: {}", file.path, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
 
-        let final_user_prompt_content = match self.get_prompt_with_structs(user_prompt_template_path, struct_source_path).await {
-            Ok(prompt) => prompt,
-            Err(e) => {
-                return Err(format!("Error preparing user prompt with structs: {}", e));
+        let prompts = match &skeleton {
+            Some(_) => {
+                let instructions = read_to_string("src/prompts/rust_skeleton.txt")
+                    .map_err(|e| format!("Error reading Rust skeleton prompt: {}", e))?;
+                (
+                    instructions,
+                    "Understood. I will return the skeleton with descriptions and calls filled in, without changing its structure.".to_string(),
+                )
             }
+            None => {
+                let user_prompt_template_path = Path::new("src/prompts/user.txt");
+                let struct_source_path = Path::new("src/services/yaml/mod.rs");
+                let model_prompt_path = Path::new("src/prompts/model.txt");
+
+                let final_user_prompt_content = match self.get_prompt_with_structs(user_prompt_template_path, struct_source_path).await {
+                    Ok(prompt) => prompt,
+                    Err(e) => {
+                        return Err(format!("Error preparing user prompt with structs: {}", e));
+                    }
+                };
+                let model_initial_response = read_to_string(model_prompt_path).unwrap_or_else(|_| String::new());
+                (final_user_prompt_content, model_initial_response)
+            }
+        };
+
+        let max_chunk_chars = std::env::var("YAML_CHUNK_CHARS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_MAX_CHUNK_CHARS);
+        let chunks = split_source(&file.content, max_chunk_chars);
+        if chunks.len() > 1 {
+            println!("Converting '{}' to YAML in {} chunks", file.path, chunks.len());
+        }
+
+        let mut parts = Vec::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let part = (chunks.len() > 1).then_some((index + 1, chunks.len()));
+            let code_message = match &skeleton {
+                Some(skeleton) => {
+                    let chunk_skeleton = if part.is_some() { skeleton_within(skeleton, chunk.start_line, chunk.end_line) } else { skeleton.clone() };
+                    let skeleton_yaml = serde_yaml::to_string(&chunk_skeleton)
+                        .map_err(|e| format!("Failed to serialize Rust skeleton: {}", e))?;
+                    format!("{}```rust\n{}\n```\n\nSkeleton:\n\n```yaml\n{}```", chunk_intro(&file.path, chunk, part), chunk.text, skeleton_yaml)
+                }
+                None => {
+                    // Numbered so the model can give each symbol's start_line/end_line
+                    let numbered_content: Vec<String> = chunk
+                        .text
+                        .lines()
+                        .enumerate()
+                        .map(|(index, line)| format!("{:>5} | {}", chunk.start_line + index, line))
+                        .collect();
+                    format!("{}```\n{}\n```", chunk_intro(&file.path, chunk, part), numbered_content.join("\n"))
+                }
+            };
+            parts.push(self.convert_chunk_to_yaml(&file.path, &prompts, code_message, provider, model_to_use, config.as_ref()).await?);
+        }
+
+        let yaml_data = if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            let descriptions: Vec<String> = parts.iter().map(|part| part.description.clone()).collect();
+            let mut merged = merge_chunk_yaml(parts);
+            merged.description = match self.synthesize_file_description(&file.path, &descriptions, provider, model_to_use, config.as_ref()).await {
+                Ok(description) => description,
+                Err(e) => {
+                    eprintln!("Falling back to the chunk descriptions of '{}': {}", file.path, e);
+                    descriptions.join(" ")
+                }
+            };
+            merged
         };
-        let model_initial_response = read_to_string(model_prompt_path).unwrap_or_else(|_| String::new());
+
+        finish_yaml(file, yaml_data, skeleton)
+    }
+
+    /// Patches a file's previous YAML from a diff of its source instead of converting the
+    /// whole file again. The answer is validated like a full conversion; any failure is
+    /// returned so the caller can fall back to `convert_to_yaml`.
+    pub async fn update_yaml_from_diff(
+        &self,
+        file: &ProjectFile,
+        previous_yaml: &FileYamlData,
+        diff: &str,
+        provider: &str,
+        model_to_use: Option<&str>,
+        config: Option<LlmServiceConfig>,
+    ) -> Result<String, String> {
+        let instructions = self
+            .get_prompt_with_structs(Path::new("src/prompts/yaml_update.txt"), Path::new("src/services/yaml/mod.rs"))
+            .await
+            .map_err(|e| format!("Error preparing YAML update prompt: {}", e))?;
+        let previous = FileYamlData { imports: Vec::new(), ..previous_yaml.clone() };
+        let previous = serde_yaml::to_string(&previous).map_err(|e| format!("Failed to serialize previous YAML: {}", e))?;
+
+        let messages = vec![
+            Message {
+                role: "user".to_string(),
+                content: instructions,
+            },
+            Message {
+                role: "model".to_string(),
+                content: "Understood. I will patch only the entries affected by the diff and return the complete updated YAML.".to_string(),
+            },
+            Message {
+                role: "user".to_string(),
+                content: format!("Previous YAML:\n\n```yaml\n{}```\n\nDiff:\n\n```diff\n{}\n```", previous, diff),
+            },
+        ];
+
+        let yaml = self
+            ._generate_and_validate_yaml(messages, provider, model_to_use, config.as_ref(), &file.path)
+            .await
+            .map_err(|(error_msg, _)| error_msg)?;
+        let yaml_data: FileYamlData = serde_yaml::from_str(&yaml).map_err(|e| e.to_string())?;
+        let skeleton = if Path::new(&file.path).extension().and_then(|ext| ext.to_str()) == Some("rs") {
+            rust_skeleton(&file.content).ok()
+        } else {
+            None
+        };
+        println!("Updated YAML for '{}' from its diff", file.path);
+        finish_yaml(file, yaml_data, skeleton)
+    }
+
+    /// Runs the YAML prompt on one chunk of a file, feeding parse errors back to the LLM for up
+    /// to three attempts. `prompts` are the instructions and the model's acknowledgement.
+    async fn convert_chunk_to_yaml(
+        &self,
+        file_path: &str,
+        prompts: &(String, String),
+        code_message: String,
+        provider: &str,
+        model_to_use: Option<&str>,
+        config: Option<&LlmServiceConfig>,
+    ) -> Result<FileYamlData, String> {
+        let max_attempts: u8 = 3; // Define maximum retry attempts
+        let (final_user_prompt_content, model_initial_response) = prompts;
 
         let mut last_failed_yaml: Option<String> = None;
         let mut last_error_message: Option<String> = None;
-        let mut final_successful_yaml: Option<String> = None;
 
         for attempt in 1..=max_attempts {
-            println!("Attempt {} to generate YAML for file: {}", attempt, file.path);
+            println!("Attempt {} to generate YAML for file: {}", attempt, file_path);
 
             let mut messages = vec![
                 Message {
@@ -366,32 +511,102 @@ impl LlmService {
             // Always provide the code content in the last user message
             messages.push(Message {
                 role: "user".to_string(),
-                content: format!("```\n{}\n```", file.content),
+                content: code_message.clone(),
             });
 
-            match self._generate_and_validate_yaml(messages, provider, model_to_use, config.as_ref(), &file.path).await {
+            match self._generate_and_validate_yaml(messages, provider, model_to_use, config, file_path).await {
                 Ok(yaml_content) => {
-                    println!("Successfully generated valid YAML on attempt {} for file: {}", attempt, file.path);
-                    final_successful_yaml = Some(yaml_content);
-                    break; // Success, exit retry loop
+                    println!("Successfully generated valid YAML on attempt {} for file: {}", attempt, file_path);
+                    return serde_yaml::from_str(&yaml_content).map_err(|e| e.to_string());
                 }
                 Err((error_msg, raw_extracted_yaml)) => {
-                    eprintln!("Attempt {} failed for file '{}'. Parsing error: {}", attempt, file.path, error_msg);
+                    eprintln!("Attempt {} failed for file '{}'. Parsing error: {}", attempt, file_path, error_msg);
                     last_error_message = Some(error_msg);
                     last_failed_yaml = Some(raw_extracted_yaml); // Store the raw generated YAML for feedback
                 }
             }
         }
 
-        if let Some(yaml) = final_successful_yaml {
-            Ok(yaml)
-        } else {
-            Err(format!(
-                "Failed to generate valid YAML after {} attempts for file '{}'. Last error: {}",
-                max_attempts,
-                file.path,
-                last_error_message.unwrap_or_else(|| "Unknown parsing error".to_string())
-            ))
+        Err(format!(
+            "Failed to generate valid YAML after {} attempts for file '{}'. Last error: {}",
+            max_attempts,
+            file_path,
+            last_error_message.unwrap_or_else(|| "Unknown parsing error".to_string())
+        ))
+    }
+
+    /// Final pass for chunked files: one description of the whole file from its chunks'.
+    async fn synthesize_file_description(
+        &self,
+        file_path: &str,
+        chunk_descriptions: &[String],
+        provider: &str,
+        model_to_use: Option<&str>,
+        config: Option<&LlmServiceConfig>,
+    ) -> Result<String, String> {
+        let target_model = match provider.to_lowercase().as_str() {
+            "openai" => LLM::OpenAI,
+            "anthropic" => LLM::Anthropic,
+            _ => LLM::Gemini,
+        };
+        let parts: Vec<String> = chunk_descriptions
+            .iter()
+            .enumerate()
+            .map(|(index, description)| format!("Part {}: {}", index + 1, description))
+            .collect();
+        let messages = vec![Message {
+            role: "user".to_string(),
+            content: format!(
+                "The file `{}` was too large to summarize at once, so each consecutive part was described separately:\n\n{}\n\nWrite a brief description (one to three sentences) of the purpose of the whole file. Output only the description, as plain text.",
+                file_path,
+                parts.join("\n")
+            ),
+        }];
+
+        let llm_config = config.and_then(|c| c.to_llm_config());
+        let description = target_model
+            .send_convo_message(messages, model_to_use, llm_config.as_ref())
+            .await
+            .map_err(|e| format!("LLM API error: {}", e))?;
+        let description = description.trim().trim_matches('`').trim().to_string();
+        if description.is_empty() {
+            return Err("LLM returned an empty description".to_string());
         }
+        Ok(description)
     }
-}
\ No newline at end of file
+}
+
+/// Final YAML for a file: for Rust, the parsed skeleton carrying the LLM's descriptions and
+/// calls; otherwise the LLM's YAML with its line ranges checked against the source.
+fn finish_yaml(file: &ProjectFile, mut yaml_data: FileYamlData, skeleton: Option<FileYamlData>) -> Result<String, String> {
+    let yaml_data = match skeleton {
+        Some(mut skeleton) => {
+            apply_annotations(&mut skeleton, &yaml_data);
+            skeleton
+        }
+        None => {
+            verify_line_ranges(&mut yaml_data, &file.content);
+            yaml_data
+        }
+    };
+    serde_yaml::to_string(&yaml_data).map_err(|e| format!("Failed to serialize YAML for '{}': {}", file.path, e))
+}
+
+/// Tells the model which part of a chunked file it is looking at; empty for whole files.
+fn chunk_intro(file_path: &str, chunk: &SourceChunk, part: Option<(usize, usize)>) -> String {
+    let Some((index, total)) = part else {
+        return String::new();
+    };
+    let mut intro = format!(
+        "This is part {} of {} of `{}` (lines {}-{}), which is too large to convert at once. Describe only the items in this part; the top-level `description` should summarize this part.",
+        index, total, file_path, chunk.start_line, chunk.end_line
+    );
+    if let Some(enclosing) = &chunk.enclosing {
+        intro.push_str(&format!(
+            " This part continues inside the item declared by:\n\n```\n{}\n```\n\nList the methods and fields in this part under that item (a class under `classes` with that name), not as top-level functions.",
+            enclosing
+        ));
+    }
+    intro.push_str("\n\n");
+    intro
+}
@@ -2,7 +2,10 @@
 pub mod file;
 pub mod llm_service;
 pub mod qdrant_service;
+pub mod vector_store;
 pub mod embedding_service;
+pub mod embedding_provider;
+pub mod embedding_cache;
 pub mod project_service;
 pub mod search_service;
 pub mod yaml;
@@ -10,5 +13,12 @@ pub mod template;
 pub mod clustering_service;
 pub mod utils;
 pub mod git_service;
+pub mod job_service;
 pub mod agent;
-pub mod path_utils;
\ No newline at end of file
+pub mod path_utils;
+pub mod tokenizer;
+pub mod rank_fusion;pub mod duplicate_service;
+pub mod search_eval;
+pub mod rerank_service;
+pub mod chat_search_service;
+pub mod notes_service;
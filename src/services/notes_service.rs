@@ -0,0 +1,311 @@
+// src/services/notes_service.rs
+use crate::models::Project;
+use crate::services::embedding_service::EmbeddingService;
+use crate::services::vector_store::synced_collection::{sync_collection, SyncDocument, SyncReport};
+use crate::services::vector_store::{self, payload_string};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Notes with this tag are included in every chat, whatever the question.
+pub const ALWAYS_TAG: &str = "always";
+/// Most notes retrieved by similarity for one message.
+const MAX_RELEVANT_NOTES: usize = 3;
+/// Similarity below which a note isn't considered relevant.
+const MIN_NOTE_SCORE: f32 = 0.3;
+
+/// A markdown note about the project (a convention, a gotcha, a decision), stored as
+/// `output/<project>/notes/<id>.md` with YAML front matter for the title and tags.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectNote {
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub body: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Fields a client sends to create or replace a note.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoteInput {
+    pub title: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoteFrontMatter {
+    title: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// Parses a note file. Files without front matter (e.g. written by hand) are accepted too:
+/// the first line, minus any leading `#`, becomes the title.
+pub fn parse_note(id: &str, text: &str) -> Result<ProjectNote, String> {
+    let front_matter = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n").or_else(|| rest.strip_suffix("\n---").map(|fm| (fm, ""))));
+
+    match front_matter {
+        Some((yaml, body)) => {
+            let front: NoteFrontMatter =
+                serde_yaml::from_str(yaml).map_err(|e| format!("Invalid front matter in note {}: {}", id, e))?;
+            Ok(ProjectNote {
+                id: id.to_string(),
+                title: front.title,
+                tags: front.tags,
+                body: body.trim_start_matches('\n').to_string(),
+                created_at: front.created_at,
+                updated_at: front.updated_at,
+            })
+        }
+        None => Ok(ProjectNote {
+            id: id.to_string(),
+            title: text.lines().next().unwrap_or_default().trim_start_matches('#').trim().to_string(),
+            tags: Vec::new(),
+            body: text.to_string(),
+            created_at: None,
+            updated_at: None,
+        }),
+    }
+}
+
+pub fn render_note(note: &ProjectNote) -> Result<String, String> {
+    let front = NoteFrontMatter {
+        title: note.title.clone(),
+        tags: note.tags.clone(),
+        created_at: note.created_at,
+        updated_at: note.updated_at,
+    };
+    let yaml = serde_yaml::to_string(&front).map_err(|e| format!("Failed to serialize note: {}", e))?;
+    Ok(format!("---\n{}---\n{}", yaml, note.body))
+}
+
+/// Note ids become file names, so only `[A-Za-z0-9_-]` is allowed.
+fn valid_note_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+pub struct NotesService;
+
+impl NotesService {
+    pub fn new() -> Self {
+        NotesService
+    }
+
+    fn notes_dir(&self, project_dir: &Path) -> PathBuf {
+        project_dir.join("notes")
+    }
+
+    fn note_path(&self, project_dir: &Path, id: &str) -> Result<PathBuf, String> {
+        if !valid_note_id(id) {
+            return Err(format!("Invalid note id: {}", id));
+        }
+        Ok(self.notes_dir(project_dir).join(format!("{}.md", id)))
+    }
+
+    /// All notes, most recently updated first. Unreadable files are skipped with a warning.
+    pub fn list(&self, project_dir: &Path) -> Vec<ProjectNote> {
+        let Ok(entries) = std::fs::read_dir(self.notes_dir(project_dir)) else {
+            return Vec::new();
+        };
+        let mut notes: Vec<ProjectNote> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("md"))
+            .filter_map(|path| {
+                let id = path.file_stem()?.to_str()?.to_string();
+                match std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| parse_note(&id, &text)) {
+                    Ok(note) => Some(note),
+                    Err(e) => {
+                        eprintln!("Skipping note {:?}: {}", path, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        notes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| a.title.cmp(&b.title)));
+        notes
+    }
+
+    pub fn get(&self, project_dir: &Path, id: &str) -> Result<Option<ProjectNote>, String> {
+        let path = self.note_path(project_dir, id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
+        parse_note(id, &text).map(Some)
+    }
+
+    fn write(&self, project_dir: &Path, note: &ProjectNote) -> Result<(), String> {
+        let path = self.note_path(project_dir, &note.id)?;
+        std::fs::create_dir_all(self.notes_dir(project_dir)).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+        std::fs::write(&path, render_note(note)?).map_err(|e| format!("Failed to write note: {}", e))
+    }
+
+    pub fn create(&self, project_dir: &Path, input: NoteInput) -> Result<ProjectNote, String> {
+        if input.title.trim().is_empty() {
+            return Err("A note needs a title".to_string());
+        }
+        let now = Utc::now();
+        let note = ProjectNote {
+            id: Uuid::new_v4().to_string(),
+            title: input.title.trim().to_string(),
+            tags: clean_tags(input.tags),
+            body: input.body,
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        self.write(project_dir, &note)?;
+        Ok(note)
+    }
+
+    /// Replaces a note's title, tags and body. `Ok(None)` if there is no such note.
+    pub fn update(&self, project_dir: &Path, id: &str, input: NoteInput) -> Result<Option<ProjectNote>, String> {
+        if input.title.trim().is_empty() {
+            return Err("A note needs a title".to_string());
+        }
+        let Some(existing) = self.get(project_dir, id)? else {
+            return Ok(None);
+        };
+        let note = ProjectNote {
+            title: input.title.trim().to_string(),
+            tags: clean_tags(input.tags),
+            body: input.body,
+            updated_at: Some(Utc::now()),
+            ..existing
+        };
+        self.write(project_dir, &note)?;
+        Ok(Some(note))
+    }
+
+    /// Returns false if there was no such note.
+    pub fn delete(&self, project_dir: &Path, id: &str) -> Result<bool, String> {
+        let path = self.note_path(project_dir, id)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete note: {}", e))?;
+        Ok(true)
+    }
+
+    /// Embeds new and edited notes into `project_<name>_notes` and drops deleted ones.
+    pub async fn sync_index(&self, project: &Project, project_dir: &Path) -> Result<SyncReport, String> {
+        let documents = self
+            .list(project_dir)
+            .into_iter()
+            .map(|note| {
+                let mut payload = HashMap::new();
+                payload.insert("note_id".to_string(), Value::from(note.id.as_str()));
+                payload.insert("title".to_string(), Value::from(note.title.as_str()));
+                payload.insert("tags".to_string(), Value::from(note.tags.clone()));
+                SyncDocument {
+                    key: note.id,
+                    text: format!("{}\nTags: {}\n\n{}", note.title, note.tags.join(", "), note.body),
+                    payload,
+                }
+            })
+            .collect();
+        sync_collection(project, project_dir, &notes_collection_name(project), "notes_index.json", documents).await
+    }
+
+    /// Notes to show the model for `query`: every note tagged `always`, plus the few most
+    /// similar ones above `MIN_NOTE_SCORE`.
+    pub async fn relevant_notes(&self, project: &Project, project_dir: &Path, query: &str) -> Result<Vec<ProjectNote>, String> {
+        let notes = self.list(project_dir);
+        if notes.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.sync_index(project, project_dir).await?;
+
+        let query_embedding = EmbeddingService::for_project(project_dir)
+            .generate_embedding(query, &project.embedding_config)
+            .await
+            .map_err(|e| e.to_string())?;
+        let vector_store = vector_store::open_project_store(project, project_dir)
+            .await
+            .map_err(|e| format!("Failed to open vector store: {}", e))?;
+        let points = vector_store
+            .search_points(&notes_collection_name(project), query_embedding, MAX_RELEVANT_NOTES as u64, false, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        let similar: Vec<String> = points
+            .into_iter()
+            .filter(|point| point.score >= MIN_NOTE_SCORE)
+            .map(|point| payload_string(&point.payload, "note_id"))
+            .collect();
+
+        let mut relevant: Vec<ProjectNote> = notes
+            .iter()
+            .filter(|note| note.tags.iter().any(|tag| tag == ALWAYS_TAG))
+            .cloned()
+            .collect();
+        for id in similar {
+            if let Some(note) = notes.iter().find(|note| note.id == id && !relevant.contains(note)) {
+                relevant.push(note.clone());
+            }
+        }
+        Ok(relevant)
+    }
+}
+
+/// Name of the collection holding a project's note embeddings.
+pub fn notes_collection_name(project: &Project) -> String {
+    format!("project_{}_notes", project.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_front_matter() {
+        let note = ProjectNote {
+            id: "n1".to_string(),
+            title: "Routes return JSON errors".to_string(),
+            tags: vec!["api".to_string(), "always".to_string()],
+            body: "Use `HttpResponse::BadRequest().json(...)`.\n\n---\nNot front matter.\n".to_string(),
+            created_at: Some("2024-05-01T10:00:00Z".parse().unwrap()),
+            updated_at: None,
+        };
+        let parsed = parse_note("n1", &render_note(&note).unwrap()).unwrap();
+        assert_eq!(parsed, note);
+    }
+
+    #[test]
+    fn accepts_plain_markdown() {
+        let note = parse_note("paths", "# Paths in embeddings are absolute\n\nThey include the source dir.").unwrap();
+        assert_eq!(note.title, "Paths in embeddings are absolute");
+        assert!(note.tags.is_empty());
+        assert!(note.body.contains("source dir"));
+    }
+
+    #[test]
+    fn rejects_ids_that_escape_the_notes_dir() {
+        assert!(valid_note_id("3f2a-notes_1"));
+        assert!(!valid_note_id("../project_settings"));
+        assert!(!valid_note_id(""));
+    }
+}
@@ -1,5 +1,6 @@
 // src/services/project_service.rs
-use crate::models::Project;
+use crate::models::{EmbeddingMetadata, Project};
+use std::collections::HashMap;
 use crate::services::yaml::YamlService;
 use crate::services::template::TemplateService;
 use crate::services::yaml::management::cleanup::clean_up_orphaned_files;
@@ -41,7 +42,7 @@ impl ProjectService {
             }
         }
 
-        clean_up_orphaned_files(&project.name, files_to_remove.clone());
+        clean_up_orphaned_files(project, output_dir, files_to_remove.clone());
         
         // remove files from embeddings after cleanup
         for file_path in files_to_remove {
@@ -68,10 +69,44 @@ impl ProjectService {
         let project_settings_json = serde_json::to_string_pretty(project)
             .map_err(|e| format!("Failed to serialize project: {}", e))?;
 
-        std::fs::write(&project_settings_path, project_settings_json)
+        // Write-then-rename so readers never see a half-written settings file
+        let tmp_path = output_dir.join("project_settings.json.tmp");
+        std::fs::write(&tmp_path, project_settings_json)
+            .map_err(|e| format!("Failed to write project settings: {}", e))?;
+        std::fs::rename(&tmp_path, &project_settings_path)
             .map_err(|e| format!("Failed to write project settings: {}", e))
     }
 
+    /// Writes embedding metadata produced by a background task into the latest saved project,
+    /// so settings changed while the task was running are not overwritten.
+    pub fn save_embedding_updates(&self, output_dir: &Path, updates: HashMap<String, EmbeddingMetadata>) -> Result<(), String> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        self.update_saved_project(output_dir, |latest| {
+            for (file_path, metadata) in updates {
+                latest.embeddings.insert(file_path, metadata);
+            }
+            Some(())
+        })
+        .map(|_| ())
+    }
+
+    /// Applies `change` to the latest saved project and saves it, so a long-running task only
+    /// writes the fields it changes. Nothing is saved when `change` returns `None`.
+    pub fn update_saved_project<T>(
+        &self,
+        output_dir: &Path,
+        change: impl FnOnce(&mut Project) -> Option<T>,
+    ) -> Result<Option<T>, String> {
+        let mut latest = self.load_project(output_dir)?;
+        let Some(result) = change(&mut latest) else {
+            return Ok(None);
+        };
+        self.save_project(&latest, output_dir)?;
+        Ok(Some(result))
+    }
+
     pub fn get_yaml_files_html(&self, output_dir: &Path, project_name: &str) -> Result<String, String> {
         let mut project = self.load_project(output_dir)?;
         let yaml_service = YamlService::new();
@@ -86,7 +121,7 @@ impl ProjectService {
 
         // Handle orphaned files cleanup
         if !orphaned_files.is_empty() {
-            yaml_service.clean_up_orphaned_files(project_name, orphaned_files);
+            yaml_service.clean_up_orphaned_files(&project, output_dir, orphaned_files);
         }
 
         // Save project if needed
@@ -1,212 +1,319 @@
 // src/services/qdrant_service.rs
+use crate::services::vector_store::payload::{Comparison, FilterCondition};
+use crate::services::vector_store::{PayloadFilter, ScoredPoint, VectorPoint, VectorStore, VectorStoreResult};
+use async_trait::async_trait;
 use qdrant_client::config::QdrantConfig;
+use qdrant_client::qdrant::point_id::PointIdOptions;
 use qdrant_client::qdrant::vectors_config::Config;
 use qdrant_client::qdrant::{
-    Condition, CreateCollection, DeletePointsBuilder, Distance, Filter, PointStruct, SearchPoints,
-    UpsertPointsBuilder, Value, VectorParams, VectorsConfig,
-    WithPayloadSelector,
+    Condition, CreateCollection, CreateFieldIndexCollectionBuilder, DeletePointsBuilder, Distance,
+    FieldType, Filter, GetPointsBuilder, PointId, PointStruct, PointsIdsList, Range,
+    ScrollPointsBuilder, SearchPoints, UpsertPointsBuilder, Value, VectorParams, VectorsConfig,
+    VectorsOutput, WithPayloadSelector,
 };
 use qdrant_client::Qdrant;
 use std::collections::HashMap;
 use std::error::Error;
-use uuid::Uuid;
+
+/// Number of points fetched per scroll request.
+const SCROLL_PAGE_SIZE: u32 = 256;
+
+/// Payload fields indexed for filtered search.
+const INDEXED_PAYLOAD_FIELDS: [(&str, FieldType); 5] = [
+    ("relative_dir", FieldType::Keyword),
+    ("extension", FieldType::Keyword),
+    ("language", FieldType::Keyword),
+    ("is_yaml", FieldType::Bool),
+    ("line_count", FieldType::Integer),
+];
+
+/// Path globs can't be expressed as a Qdrant filter, so searches with them fetch larger pages.
+const PATH_FILTER_OVERSAMPLE: u64 = 4;
 
 pub struct QdrantService {
     client: Qdrant,
-    vector_size: u64,
 }
 
 impl QdrantService {
-    pub async fn new(url: &str, vector_size: u64) -> Result<Self, Box<dyn Error + Send + Sync>> {
+    pub async fn new(url: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let config = QdrantConfig::from_url(url);
-        let client = Qdrant::new(config).unwrap(); // Consider using ? for error propagation
-        Ok(QdrantService {
-            client,
-            vector_size,
-        })
+        let client = Qdrant::new(config)?;
+        Ok(QdrantService { client })
     }
 
-    pub async fn create_project_collection(
-        &self,
-        project_name: &str,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let collection_name = format!("project_{}", project_name);
-
-        let collections = self.client.list_collections().await?;
-        if collections
-            .collections
-            .iter()
-            .any(|c| &c.name == &collection_name)
-        {
-            return Ok(());
+    /// Pages through every point of the collection, with or without vectors.
+    async fn scroll(&self, collection_name: &str, with_vectors: bool) -> VectorStoreResult<Vec<VectorPoint>> {
+        let mut points = Vec::new();
+        let mut offset: Option<PointId> = None;
+
+        loop {
+            let mut request = ScrollPointsBuilder::new(collection_name)
+                .limit(SCROLL_PAGE_SIZE)
+                .with_payload(true)
+                .with_vectors(with_vectors);
+            if let Some(offset) = offset.take() {
+                request = request.offset(offset);
+            }
+
+            let response = self.client.scroll(request).await?;
+
+            points.extend(response.result.into_iter().map(|point| VectorPoint {
+                id: point_id_to_string(point.id),
+                vector: vectors_to_vec(point.vectors).unwrap_or_default(),
+                payload: payload_to_json(point.payload),
+            }));
+
+            match response.next_page_offset {
+                Some(next) => offset = Some(next),
+                None => break,
+            }
         }
 
-        self.client
-            .create_collection(CreateCollection {
-                collection_name: collection_name.clone(),
-                vectors_config: Some(VectorsConfig {
-                    config: Some(Config::Params(VectorParams {
-                        size: self.vector_size,
-                        distance: Distance::Cosine.into(),
-                        ..Default::default()
-                    })),
-                }),
-                ..Default::default()
-            })
-            .await?;
+        Ok(points)
+    }
+}
 
-        Ok(())
+fn point_id_to_string(id: Option<PointId>) -> String {
+    match id.and_then(|id| id.point_id_options) {
+        Some(PointIdOptions::Uuid(uuid)) => uuid,
+        Some(PointIdOptions::Num(num)) => num.to_string(),
+        None => String::new(),
     }
+}
 
-    pub async fn store_file_embedding(
-        &self,
-        project_name: &str,
-        file_path: &str,
-        file_content: &str, // Changed name for clarity from example, assuming this is payload content
-        embedding: Vec<f32>,
-    ) -> Result<String, Box<dyn Error + Send + Sync>> {
-        let collection_name = format!("project_{}", project_name);
-        let point_id = Uuid::new_v4().to_string();
+fn payload_to_json(payload: HashMap<String, Value>) -> HashMap<String, serde_json::Value> {
+    payload
+        .into_iter()
+        .map(|(key, value)| (key, value.into_json()))
+        .collect()
+}
 
-        println!(
-            "Attempting to delete existing points for file: {}",
-            file_path
-        ); // Added log
-        self.client
-            .delete_points(
-                DeletePointsBuilder::new(collection_name.clone()) // Clone collection_name as it's used later
-                    .points(Filter::must([Condition::matches(
-                        // Use Filter::must and Condition::matches
-                        "file_path".to_string(),
-                        file_path.to_string(), // Match the file_path field exactly
-                    )]))
-                    .wait(true), // Wait for the deletion to complete
-            )
-            .await?;
-        println!("Deletion request completed for file: {}", file_path); // Added log
-
-        // Create payload with file metadata
-        let mut payload = HashMap::new();
-        payload.insert("file_path".to_string(), Value::from(file_path));
-        // Assuming the content you want to store is file_content, not yaml_content
-        payload.insert("file_content".to_string(), Value::from(file_content));
-
-        // Create the point struct with explicit vector assignment
-        let point = PointStruct::new(
-            point_id.clone(),
-            embedding, // This is the key fix
-            payload,
-        );
+/// The parts of `filter` Qdrant can evaluate itself; path globs are checked afterwards.
+fn qdrant_filter(filter: &PayloadFilter) -> Option<Filter> {
+    let mut must = Vec::new();
+    let mut must_not = Vec::new();
+    for term in &filter.terms {
+        let condition = match &term.condition {
+            FilterCondition::Path(_) => continue,
+            FilterCondition::Extension(values) => Condition::matches("extension", values.clone()),
+            FilterCondition::Language(values) => Condition::matches("language", values.clone()),
+            FilterCondition::Yaml(is_yaml) => Condition::matches("is_yaml", *is_yaml),
+            FilterCondition::Lines(comparison, bound) => {
+                let bound = Some(*bound as f64);
+                let range = match comparison {
+                    Comparison::Less => Range { lt: bound, ..Default::default() },
+                    Comparison::LessOrEqual => Range { lte: bound, ..Default::default() },
+                    Comparison::Greater => Range { gt: bound, ..Default::default() },
+                    Comparison::GreaterOrEqual => Range { gte: bound, ..Default::default() },
+                    Comparison::Equal => Range { gte: bound, lte: bound, ..Default::default() },
+                };
+                Condition::range("line_count", range)
+            }
+        };
+        if term.negated {
+            must_not.push(condition);
+        } else {
+            must.push(condition);
+        }
+    }
+    if must.is_empty() && must_not.is_empty() {
+        return None;
+    }
+    Some(Filter { must, must_not, ..Default::default() })
+}
 
-        println!(
-            "Sending embedding for point_id.clone(): {}",
-            point_id.clone()
-        );
-        println!("Vector data present: {}", point.vectors.is_some()); // Check if vectors are set
+fn vectors_to_vec(vectors: Option<VectorsOutput>) -> Option<Vec<f32>> {
+    vectors.and_then(|v| match v.vectors_options {
+        Some(qdrant_client::qdrant::vectors_output::VectorsOptions::Vector(vector_output)) => {
+            Some(vector_output.data)
+        }
+        _ => None,
+    })
+}
 
-        self.client
-            .upsert_points(UpsertPointsBuilder::new(collection_name, vec![point]).wait(true))
-            .await?;
+#[async_trait]
+impl VectorStore for QdrantService {
+    async fn create_collection(&self, collection_name: &str, vector_size: u64) -> VectorStoreResult<()> {
+        if !self.client.collection_exists(collection_name).await? {
+            self.client
+                .create_collection(CreateCollection {
+                    collection_name: collection_name.to_string(),
+                    vectors_config: Some(VectorsConfig {
+                        config: Some(Config::Params(VectorParams {
+                            size: vector_size,
+                            distance: Distance::Cosine.into(),
+                            ..Default::default()
+                        })),
+                    }),
+                    ..Default::default()
+                })
+                .await?;
+        }
 
-        println!("Upsert successful for file: {}", file_path); // Added log
+        // Collections created before filtering existed get their missing indexes here
+        let indexed = self
+            .client
+            .collection_info(collection_name)
+            .await?
+            .result
+            .map(|info| info.payload_schema)
+            .unwrap_or_default();
+        for (field_name, field_type) in INDEXED_PAYLOAD_FIELDS {
+            if indexed.contains_key(field_name) {
+                continue;
+            }
+            self.client
+                .create_field_index(CreateFieldIndexCollectionBuilder::new(collection_name, field_name, field_type))
+                .await?;
+        }
 
-        Ok(point_id)
+        Ok(())
     }
 
-    // In qdrant_service.rs
-    pub async fn search_similar_files(
-        &self,
-        project_name: &str,
-        query_embedding: Vec<f32>,
-        limit: u64,
-        return_embeddings: bool,
-    ) -> Result<Vec<(String, String, f32, Option<Vec<f32>>)>, Box<dyn std::error::Error + Send + Sync>> {
-        let collection_name = format!("project_{}", project_name);
-
-        let search_request = SearchPoints {
-            collection_name: collection_name.clone(), // Clone if needed elsewhere, good practice
-            vector: query_embedding,
-            limit: limit,
-            with_payload: Some(WithPayloadSelector::from(true)),
-            with_vectors: Some(return_embeddings.into()),
-            ..Default::default()
-        };
+    async fn delete_collection(&self, collection_name: &str) -> VectorStoreResult<()> {
+        if self.client.collection_exists(collection_name).await? {
+            self.client.delete_collection(collection_name).await?;
+        }
+        Ok(())
+    }
 
-        let search_result = self.client.search_points(search_request).await?;
+    async fn upsert_points(&self, collection_name: &str, points: Vec<VectorPoint>) -> VectorStoreResult<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
 
-        let results = search_result
-            .result
+        let points: Vec<PointStruct> = points
             .into_iter()
             .map(|point| {
-                let file_path = point
+                let payload: HashMap<String, Value> = point
                     .payload
-                    .get("file_path")
-                    .and_then(|v| v.kind.as_ref())
-                    .and_then(|k| {
-                        if let qdrant_client::qdrant::value::Kind::StringValue(s) = k {
-                            Some(s.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or_default();
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect();
+                PointStruct::new(point.id, point.vector, payload)
+            })
+            .collect();
 
-                // Corrected payload key lookup
-                let file_content = point
-                    .payload
-                    .get("file_content")
-                    .and_then(|v| v.kind.as_ref())
-                    .and_then(|k| {
-                        if let qdrant_client::qdrant::value::Kind::StringValue(s) = k {
-                            Some(s.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or_default();
+        self.client
+            .upsert_points(UpsertPointsBuilder::new(collection_name, points).wait(true))
+            .await?;
 
-                // Extract vectors if present
-                let vectors = point.vectors.and_then(|v| match v.vectors_options {
-                    Some(qdrant_client::qdrant::vectors_output::VectorsOptions::Vector(
-                        vector_output,
-                    )) => Some(vector_output.data),
-                    _ => None,
-                });
+        Ok(())
+    }
 
-                (file_path, file_content, point.score, vectors)
-            })
-            .collect();
+    async fn delete_points(&self, collection_name: &str, point_ids: Vec<String>) -> VectorStoreResult<()> {
+        if point_ids.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<PointId> = point_ids.into_iter().map(PointId::from).collect();
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(collection_name)
+                    .points(PointsIdsList { ids })
+                    .wait(true),
+            )
+            .await?;
 
-        Ok(results)
+        Ok(())
     }
 
-    // Add this method to QdrantService implementation
-    pub async fn delete_file_vectors(
+    async fn get_point(&self, collection_name: &str, point_id: &str) -> VectorStoreResult<Option<VectorPoint>> {
+        let response = self
+            .client
+            .get_points(
+                GetPointsBuilder::new(collection_name, vec![PointId::from(point_id.to_string())])
+                    .with_payload(true)
+                    .with_vectors(true),
+            )
+            .await?;
+
+        Ok(response.result.into_iter().next().map(|point| VectorPoint {
+            id: point_id_to_string(point.id),
+            vector: vectors_to_vec(point.vectors).unwrap_or_default(),
+            payload: payload_to_json(point.payload),
+        }))
+    }
+
+    async fn delete_stale_file_vectors(
         &self,
-        project_name: &str,
+        collection_name: &str,
         file_path: &str,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let collection_name = format!("project_{}", project_name);
-
+        keep_ids: &[String],
+    ) -> VectorStoreResult<()> {
         println!(
             "Deleting vectors for file: {} in collection {}",
             file_path, collection_name
         );
 
-        // Use DeletePointsBuilder to delete points based on file_path
+        let mut filter = Filter::must([Condition::matches("file_path".to_string(), file_path.to_string())]);
+        if !keep_ids.is_empty() {
+            filter.must_not.push(Condition::has_id(keep_ids.iter().cloned().map(PointId::from)));
+        }
         self.client
-            .delete_points(
-                DeletePointsBuilder::new(collection_name)
-                    .points(Filter::must([Condition::matches(
-                        "file_path".to_string(),
-                        file_path.to_string(),
-                    )]))
-                    .wait(true),
-            )
+            .delete_points(DeletePointsBuilder::new(collection_name).points(filter).wait(true))
             .await?;
 
         println!("Successfully deleted vectors for file: {}", file_path);
 
         Ok(())
     }
+
+    async fn search_points(
+        &self,
+        collection_name: &str,
+        query_vector: Vec<f32>,
+        limit: u64,
+        with_vectors: bool,
+        filter: Option<&PayloadFilter>,
+    ) -> VectorStoreResult<Vec<ScoredPoint>> {
+        let has_path_terms = filter.is_some_and(|filter| {
+            filter.terms.iter().any(|term| matches!(term.condition, FilterCondition::Path(_)))
+        });
+        let page_size = if has_path_terms { limit * PATH_FILTER_OVERSAMPLE } else { limit };
+
+        // Conditions Qdrant can't evaluate are checked here, page by page, until `limit`
+        // points match or the search runs out of points
+        let mut points = Vec::new();
+        let mut offset = 0;
+        loop {
+            let search_request = SearchPoints {
+                collection_name: collection_name.to_string(),
+                vector: query_vector.clone(),
+                limit: page_size,
+                offset: Some(offset),
+                filter: filter.and_then(qdrant_filter),
+                with_payload: Some(WithPayloadSelector::from(true)),
+                with_vectors: Some(with_vectors.into()),
+                ..Default::default()
+            };
+            let page = self.client.search_points(search_request).await?.result;
+            let exhausted = (page.len() as u64) < page_size;
+            offset += page.len() as u64;
+
+            // Re-checked here too, since points without metadata pass negated conditions in Qdrant
+            points.extend(
+                page.into_iter()
+                    .map(|point| ScoredPoint {
+                        score: point.score,
+                        payload: payload_to_json(point.payload),
+                        vector: vectors_to_vec(point.vectors),
+                    })
+                    .filter(|point| filter.is_none_or(|filter| filter.matches_payload(&point.payload))),
+            );
+            if exhausted || points.len() as u64 >= limit {
+                break;
+            }
+        }
+        points.truncate(limit as usize);
+        Ok(points)
+    }
+
+    async fn scroll_points(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>> {
+        self.scroll(collection_name, true).await
+    }
+
+    async fn scroll_payloads(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>> {
+        self.scroll(collection_name, false).await
+    }
 }
@@ -0,0 +1,139 @@
+// src/services/rank_fusion.rs
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Retrieval sources that hybrid search fuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSource {
+    Vector,
+    Keyword,
+    Symbol,
+}
+
+/// 1-based rank of a result in each source it came from, `None` if that source missed it.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct SourceRanks {
+    pub vector: Option<usize>,
+    pub keyword: Option<usize>,
+    pub symbol: Option<usize>,
+}
+
+impl SourceRanks {
+    fn set(&mut self, source: SearchSource, rank: usize) {
+        let slot = match source {
+            SearchSource::Vector => &mut self.vector,
+            SearchSource::Keyword => &mut self.keyword,
+            SearchSource::Symbol => &mut self.symbol,
+        };
+        *slot = Some(rank);
+    }
+
+    /// Short human readable form, e.g. "vector #1, keyword #4".
+    pub fn describe(&self) -> String {
+        [("vector", self.vector), ("keyword", self.keyword), ("symbol", self.symbol)]
+            .iter()
+            .filter_map(|(name, rank)| rank.map(|rank| format!("{} #{}", name, rank)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One source's results, best first, with the weight its contribution is multiplied by.
+pub struct RankedList {
+    pub source: SearchSource,
+    pub weight: f32,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FusedHit {
+    pub key: String,
+    pub score: f32,
+    pub ranks: SourceRanks,
+}
+
+/// Reciprocal rank fusion: every list adds `weight / (k + rank)` to each key it contains.
+/// Only ranks matter, so sources with incomparable scores (cosine, BM25F) can be merged.
+/// Duplicate keys within one list keep their first (best) rank. Ties sort by key.
+pub fn reciprocal_rank_fusion(lists: &[RankedList], k: f32) -> Vec<FusedHit> {
+    let mut fused: HashMap<&str, FusedHit> = HashMap::new();
+
+    for list in lists {
+        let mut rank = 0;
+        for key in &list.keys {
+            let hit = fused.entry(key.as_str()).or_insert_with(|| FusedHit {
+                key: key.clone(),
+                score: 0.0,
+                ranks: SourceRanks::default(),
+            });
+            let already_ranked = match list.source {
+                SearchSource::Vector => hit.ranks.vector.is_some(),
+                SearchSource::Keyword => hit.ranks.keyword.is_some(),
+                SearchSource::Symbol => hit.ranks.symbol.is_some(),
+            };
+            if already_ranked {
+                continue;
+            }
+            rank += 1;
+            hit.ranks.set(list.source, rank);
+            hit.score += list.weight / (k + rank as f32);
+        }
+    }
+
+    let mut hits: Vec<FusedHit> = fused.into_values().collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(source: SearchSource, weight: f32, keys: &[&str]) -> RankedList {
+        RankedList {
+            source,
+            weight,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn file_found_by_several_sources_wins() {
+        let hits = reciprocal_rank_fusion(
+            &[
+                list(SearchSource::Vector, 1.0, &["a", "b", "c"]),
+                list(SearchSource::Keyword, 1.0, &["c", "d"]),
+            ],
+            60.0,
+        );
+        assert_eq!(hits[0].key, "c");
+        assert_eq!(hits[0].ranks, SourceRanks { vector: Some(3), keyword: Some(1), symbol: None });
+        assert_eq!(hits.len(), 4);
+    }
+
+    #[test]
+    fn weights_scale_a_sources_contribution() {
+        let hits = reciprocal_rank_fusion(
+            &[
+                list(SearchSource::Vector, 1.0, &["a"]),
+                list(SearchSource::Keyword, 3.0, &["b"]),
+            ],
+            60.0,
+        );
+        assert_eq!(hits[0].key, "b");
+        assert!((hits[0].score - 3.0 / 61.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn duplicates_keep_best_rank() {
+        let hits = reciprocal_rank_fusion(&[list(SearchSource::Symbol, 1.0, &["a", "a", "b"])], 60.0);
+        let b = hits.iter().find(|hit| hit.key == "b").unwrap();
+        assert_eq!(b.ranks.symbol, Some(2));
+        assert_eq!(hits[0].ranks.describe(), "symbol #1");
+    }
+}
@@ -0,0 +1,208 @@
+// src/services/rerank_service.rs
+use crate::models::Project;
+use crate::services::llm_service::{LlmService, LlmServiceConfig};
+use crate::services::search_service::SearchResult;
+use crate::services::utils::llm_json::parse_llm_json;
+use crate::services::yaml::load_file_yaml;
+use git2::{ObjectType, Oid};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// How many hybrid search candidates the LLM re-ranks.
+pub const RERANK_CANDIDATES: u64 = 20;
+
+/// The LLM's judgement of one search result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankScore {
+    /// 0.0 (irrelevant) to 1.0 (exactly what the query needs)
+    pub relevance: f32,
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+struct RerankReply {
+    #[serde(default)]
+    ranking: Vec<RerankReplyEntry>,
+}
+
+#[derive(Deserialize)]
+struct RerankReplyEntry {
+    id: usize,
+    #[serde(default)]
+    relevance: f32,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Cached outcome: candidate indices in their new order with their scores.
+#[derive(Serialize, Deserialize)]
+struct CachedRanking {
+    order: Vec<(usize, RerankScore)>,
+}
+
+/// Validates the model's ranking against `candidate_count` candidates: unknown ids and
+/// repeats are dropped, relevance is clamped to 0..=1, and candidates the model left out
+/// follow in their original order with relevance 0. Sorted by relevance, ties in the model's order.
+fn validate_ranking(reply: RerankReply, candidate_count: usize) -> Vec<(usize, RerankScore)> {
+    let mut seen = HashSet::new();
+    let mut order: Vec<(usize, RerankScore)> = reply
+        .ranking
+        .into_iter()
+        .filter(|entry| entry.id < candidate_count && seen.insert(entry.id))
+        .map(|entry| {
+            let relevance = if entry.relevance.is_finite() { entry.relevance.clamp(0.0, 1.0) } else { 0.0 };
+            (entry.id, RerankScore { relevance, reason: entry.reason })
+        })
+        .collect();
+    order.sort_by(|a, b| b.1.relevance.partial_cmp(&a.1.relevance).unwrap_or(std::cmp::Ordering::Equal));
+
+    for id in (0..candidate_count).filter(|id| !seen.contains(id)) {
+        order.push((id, RerankScore { relevance: 0.0, reason: "Not ranked by the model".to_string() }));
+    }
+    order
+}
+
+pub struct RerankService {
+    cache_dir: PathBuf,
+}
+
+impl RerankService {
+    /// Re-ranker caching its answers under `output/<project>/rerank_cache/`.
+    pub fn for_project(project_dir: &Path) -> Self {
+        RerankService {
+            cache_dir: project_dir.join("rerank_cache"),
+        }
+    }
+
+    /// Hash of everything that influences the ranking: model, query and each candidate
+    /// with the description shown for it.
+    fn cache_key(project: &Project, query: &str, candidate_lines: &[String]) -> String {
+        let keyed = format!(
+            "{}\n{}\n{}\n{}",
+            project.provider,
+            project.specific_model.as_deref().unwrap_or(""),
+            query,
+            candidate_lines.join("\n")
+        );
+        Oid::hash_object(ObjectType::Blob, keyed.as_bytes())
+            .map(|oid| oid.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Asks the LLM to order `candidates` by relevance to `query` from their YAML descriptions.
+    /// Every candidate is returned exactly once, with `rerank` set.
+    pub async fn rerank(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        query: &str,
+        candidates: Vec<SearchResult>,
+    ) -> Result<Vec<SearchResult>, String> {
+        if candidates.len() < 2 {
+            return Ok(candidates);
+        }
+
+        let candidate_lines: Vec<String> = candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| {
+                let description = load_file_yaml(project_dir, &candidate.file_path)
+                    .map(|yaml| yaml.description)
+                    .or_else(|| candidate.file_description.clone())
+                    .unwrap_or_default();
+                let path = candidate
+                    .file_path
+                    .strip_prefix(&project.source_dir)
+                    .map(|p| p.trim_start_matches('/'))
+                    .unwrap_or(&candidate.file_path);
+                format!("{}. {}: {}", id, path, description.replace('\n', " "))
+            })
+            .collect();
+
+        let key = Self::cache_key(project, query, &candidate_lines);
+        let cache_path = self.cache_dir.join(format!("{}.json", key));
+        let cached = std::fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<CachedRanking>(&json).ok())
+            .filter(|cached| cached.order.len() == candidates.len());
+
+        let order = match cached {
+            Some(cached) => cached.order,
+            None => {
+                let order = self.ask_llm(project, query, &candidate_lines, candidates.len()).await?;
+                let cached = CachedRanking { order };
+                if let Err(e) = std::fs::create_dir_all(&self.cache_dir)
+                    .and_then(|_| std::fs::write(&cache_path, serde_json::to_string(&cached).unwrap_or_default()))
+                {
+                    eprintln!("Failed to cache re-ranking: {}", e);
+                }
+                cached.order
+            }
+        };
+
+        let mut slots: Vec<Option<SearchResult>> = candidates.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let mut result = slots.get_mut(id)?.take()?;
+                result.rerank = Some(score);
+                Some(result)
+            })
+            .collect())
+    }
+
+    async fn ask_llm(
+        &self,
+        project: &Project,
+        query: &str,
+        candidate_lines: &[String],
+        candidate_count: usize,
+    ) -> Result<Vec<(usize, RerankScore)>, String> {
+        let prompt = format!(
+            "User Query: \"{}\"\n\n\
+            Candidate files (id. path: description):\n{}\n\n\
+            Rank the candidates by how relevant each file is to the query. Reply with only a JSON code block:\n\
+            ```json\n{{\n  \"ranking\": [\n    {{\"id\": 0, \"relevance\": 0.9, \"reason\": \"short reason\"}}\n  ]\n}}\n```\n\
+            - `ranking`: every candidate id exactly once, most relevant first.\n\
+            - `relevance`: a number from 0.0 (unrelated) to 1.0 (exactly what the query needs).\n\
+            - `reason`: one short sentence.",
+            query,
+            candidate_lines.join("\n")
+        );
+        let reply = LlmService::new()
+            .get_analysis(
+                &prompt,
+                &project.provider,
+                project.specific_model.as_deref(),
+                Some(LlmServiceConfig::new().with_temperature(0.0)),
+            )
+            .await;
+        let reply: RerankReply = parse_llm_json(reply)?;
+        Ok(validate_ranking(reply, candidate_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, relevance: f32) -> RerankReplyEntry {
+        RerankReplyEntry { id, relevance, reason: String::new() }
+    }
+
+    #[test]
+    fn drops_unknown_and_repeated_ids_and_appends_missing() {
+        let reply = RerankReply { ranking: vec![entry(2, 0.9), entry(7, 1.0), entry(2, 0.1), entry(0, 0.5)] };
+        let ids: Vec<usize> = validate_ranking(reply, 4).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![2, 0, 1, 3]);
+    }
+
+    #[test]
+    fn clamps_relevance_and_sorts_by_it() {
+        let reply = RerankReply { ranking: vec![entry(0, 0.2), entry(1, 7.0), entry(2, f32::NAN)] };
+        let order = validate_ranking(reply, 3);
+        assert_eq!(order[0], (1, RerankScore { relevance: 1.0, reason: String::new() }));
+        assert_eq!(order[2].1.relevance, 0.0);
+    }
+}
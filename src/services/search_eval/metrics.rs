@@ -0,0 +1,100 @@
+// src/services/search_eval/metrics.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Ranking quality of one strategy on one query (or averaged over a query set).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RankingMetrics {
+    /// Share of the relevant files found in the top k
+    pub recall_at_k: f32,
+    /// 1 / rank of the first relevant file, 0 if none was returned
+    pub mrr: f32,
+    /// Binary-relevance nDCG over the top k
+    pub ndcg_at_k: f32,
+}
+
+impl RankingMetrics {
+    pub fn score(ranked: &[String], relevant: &HashSet<String>, k: usize) -> Self {
+        if relevant.is_empty() {
+            return RankingMetrics::default();
+        }
+        let top_k = &ranked[..ranked.len().min(k)];
+
+        let found = top_k.iter().filter(|path| relevant.contains(*path)).count();
+        let mrr = ranked
+            .iter()
+            .position(|path| relevant.contains(path))
+            .map_or(0.0, |index| 1.0 / (index + 1) as f32);
+
+        let discount = |index: usize| 1.0 / ((index + 2) as f32).log2();
+        let dcg: f32 = top_k
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| relevant.contains(*path))
+            .map(|(index, _)| discount(index))
+            .sum();
+        let ideal_dcg: f32 = (0..relevant.len().min(k)).map(discount).sum();
+
+        RankingMetrics {
+            recall_at_k: found as f32 / relevant.len() as f32,
+            mrr,
+            ndcg_at_k: if ideal_dcg > 0.0 { dcg / ideal_dcg } else { 0.0 },
+        }
+    }
+
+    pub fn mean(all: &[RankingMetrics]) -> Self {
+        if all.is_empty() {
+            return RankingMetrics::default();
+        }
+        let n = all.len() as f32;
+        RankingMetrics {
+            recall_at_k: all.iter().map(|m| m.recall_at_k).sum::<f32>() / n,
+            mrr: all.iter().map(|m| m.mrr).sum::<f32>() / n,
+            ndcg_at_k: all.iter().map(|m| m.ndcg_at_k).sum::<f32>() / n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn set(items: &[&str]) -> HashSet<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn perfect_ranking_scores_one() {
+        let metrics = RankingMetrics::score(&paths(&["a", "b", "c"]), &set(&["a", "b"]), 5);
+        assert_eq!(metrics, RankingMetrics { recall_at_k: 1.0, mrr: 1.0, ndcg_at_k: 1.0 });
+    }
+
+    #[test]
+    fn relevant_file_at_rank_two() {
+        let metrics = RankingMetrics::score(&paths(&["x", "a", "y"]), &set(&["a"]), 5);
+        assert_eq!(metrics.recall_at_k, 1.0);
+        assert_eq!(metrics.mrr, 0.5);
+        assert!((metrics.ndcg_at_k - 1.0 / 3f32.log2()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn files_beyond_k_only_count_for_mrr() {
+        let metrics = RankingMetrics::score(&paths(&["x", "y", "a"]), &set(&["a", "b"]), 2);
+        assert_eq!(metrics.recall_at_k, 0.0);
+        assert_eq!(metrics.ndcg_at_k, 0.0);
+        assert!((metrics.mrr - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mean_averages_each_metric() {
+        let mean = RankingMetrics::mean(&[
+            RankingMetrics { recall_at_k: 1.0, mrr: 1.0, ndcg_at_k: 1.0 },
+            RankingMetrics::default(),
+        ]);
+        assert_eq!(mean, RankingMetrics { recall_at_k: 0.5, mrr: 0.5, ndcg_at_k: 0.5 });
+    }
+}
@@ -0,0 +1,276 @@
+// src/services/search_eval/mod.rs
+pub mod metrics;
+
+use crate::models::{EmbeddingConfig, HybridSearchConfig, Project};
+use crate::services::clustering_service::cosine_distance;
+use crate::services::embedding_provider::local::HashingEmbedder;
+use crate::services::path_utils::PathUtils;
+use crate::services::search_service::SearchService;
+use crate::services::yaml::keyword_index;
+use crate::services::yaml::management::embedding::read_content_to_embed;
+use chrono::{DateTime, Utc};
+use metrics::RankingMetrics;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const EVAL_DIR: &str = "eval";
+const EVAL_SET_FILE: &str = "eval_set.yml";
+const DEFAULT_K: usize = 5;
+const DEFAULT_FAKE_DIMENSIONS: usize = 256;
+
+/// A labeled query: the files a good search should return for it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvalQuery {
+    pub query: String,
+    /// Project file paths, either as stored (`Project.embeddings` keys) or relative to the source dir
+    pub relevant: Vec<String>,
+}
+
+/// Labeled queries for a project, kept in `output/<project>/eval/eval_set.yml`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EvalSet {
+    #[serde(default)]
+    pub queries: Vec<EvalQuery>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalEmbedder {
+    /// The project's embedding model and stored vectors
+    #[default]
+    Project,
+    /// `HashingEmbedder` over the same content, in memory; reproducible and offline
+    Fake,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct EvalOptions {
+    pub k: Option<usize>,
+    #[serde(default)]
+    pub embedder: EvalEmbedder,
+    pub fake_dimensions: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StrategyResult {
+    pub metrics: RankingMetrics,
+    /// Top k file paths returned
+    pub top: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryEval {
+    pub query: String,
+    pub relevant: Vec<String>,
+    /// Labeled paths that don't match any file in the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown_relevant: Vec<String>,
+    pub strategies: BTreeMap<String, StrategyResult>,
+}
+
+/// One evaluation run, stored as `output/<project>/eval/runs/<id>.json`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvalRun {
+    pub id: String,
+    pub project: String,
+    pub created_at: DateTime<Utc>,
+    pub k: usize,
+    pub embedder: EvalEmbedder,
+    /// Settings the run was made with, so runs can be compared after they change
+    pub embedding_config: EmbeddingConfig,
+    pub hybrid_search: HybridSearchConfig,
+    pub default_use_yaml: bool,
+    /// Mean metrics per strategy ("vector", "keyword", "hybrid")
+    pub summary: BTreeMap<String, RankingMetrics>,
+    #[serde(default)]
+    pub queries: Vec<QueryEval>,
+}
+
+fn eval_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join(EVAL_DIR)
+}
+
+fn runs_dir(project_dir: &Path) -> PathBuf {
+    eval_dir(project_dir).join("runs")
+}
+
+pub fn load_eval_set(project_dir: &Path) -> Result<EvalSet, String> {
+    let path = eval_dir(project_dir).join(EVAL_SET_FILE);
+    if !path.exists() {
+        return Ok(EvalSet::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read eval set: {}", e))?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Invalid eval set {}: {}", path.display(), e))
+}
+
+pub fn save_eval_set(project_dir: &Path, eval_set: &EvalSet) -> Result<(), String> {
+    let dir = eval_dir(project_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let yaml = serde_yaml::to_string(eval_set).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(EVAL_SET_FILE), yaml).map_err(|e| format!("Failed to save eval set: {}", e))
+}
+
+/// Stored runs, newest first, without their per-query details.
+pub fn list_runs(project_dir: &Path) -> Vec<EvalRun> {
+    let Ok(entries) = std::fs::read_dir(runs_dir(project_dir)) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<EvalRun> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|json| serde_json::from_str::<EvalRun>(&json).ok())
+        .map(|mut run| {
+            run.queries.clear();
+            run
+        })
+        .collect();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.created_at));
+    runs
+}
+
+pub fn load_run(project_dir: &Path, run_id: &str) -> Result<EvalRun, String> {
+    if run_id.contains(['/', '\\', '.']) {
+        return Err("Invalid run id".to_string());
+    }
+    let json = std::fs::read_to_string(runs_dir(project_dir).join(format!("{}.json", run_id)))
+        .map_err(|_| format!("Eval run {} not found", run_id))?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+fn save_run(project_dir: &Path, run: &EvalRun) -> Result<(), String> {
+    let dir = runs_dir(project_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(run).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(format!("{}.json", run.id)), json).map_err(|e| format!("Failed to save eval run: {}", e))
+}
+
+/// In-memory vector index over what the project embeds for each file, built with `HashingEmbedder`.
+struct FakeVectorIndex {
+    embedder: HashingEmbedder,
+    docs: Vec<(String, Vec<f32>)>,
+}
+
+impl FakeVectorIndex {
+    fn build(project: &Project, project_dir: &Path, dimensions: usize) -> Self {
+        let embedder = HashingEmbedder::new(dimensions);
+        let mut paths: Vec<&String> = project.embeddings.keys().collect();
+        paths.sort();
+        let docs = paths
+            .into_iter()
+            .filter_map(|path| {
+                let content = read_content_to_embed(project, path, project_dir).ok()?;
+                Some((path.clone(), embedder.embed_text(&content)))
+            })
+            .collect();
+        FakeVectorIndex { embedder, docs }
+    }
+
+    fn rank(&self, query: &str, limit: usize) -> Vec<String> {
+        let query_vector = self.embedder.embed_text(query);
+        let mut scored: Vec<(&str, f32)> = self
+            .docs
+            .iter()
+            .map(|(path, vector)| (path.as_str(), 1.0 - cosine_distance(&query_vector, vector)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(b.0)));
+        scored.into_iter().take(limit).map(|(path, _)| path.to_string()).collect()
+    }
+}
+
+/// Scores vector, keyword and hybrid search on every query in the project's eval set and
+/// stores the run.
+pub async fn run_eval(
+    project_name: &str,
+    project: &Project,
+    project_dir: &Path,
+    options: &EvalOptions,
+) -> Result<EvalRun, String> {
+    let eval_set = load_eval_set(project_dir)?;
+    if eval_set.queries.is_empty() {
+        return Err(format!(
+            "No eval queries; add some to {}",
+            eval_dir(project_dir).join(EVAL_SET_FILE).display()
+        ));
+    }
+
+    let k = options.k.unwrap_or(DEFAULT_K).max(1);
+    let candidates = (project.hybrid_search.candidates_per_source as usize).max(k);
+    let fake_index = (options.embedder == EvalEmbedder::Fake).then(|| {
+        FakeVectorIndex::build(project, project_dir, options.fake_dimensions.unwrap_or(DEFAULT_FAKE_DIMENSIONS))
+    });
+    let search_service = SearchService::new();
+
+    let mut queries = Vec::new();
+    for eval_query in &eval_set.queries {
+        let mut relevant = Vec::new();
+        let mut unknown_relevant = Vec::new();
+        for path in &eval_query.relevant {
+            match PathUtils::normalize_project_path(path, project) {
+                Some(normalized) => relevant.push(normalized),
+                None => unknown_relevant.push(path.clone()),
+            }
+        }
+        let relevant_set: HashSet<String> = relevant.iter().cloned().collect();
+
+        let (vector_keys, symbol_vector_keys) = match &fake_index {
+            Some(index) => (index.rank(&eval_query.query, candidates), Vec::new()),
+            None => {
+                let rankings = search_service
+                    .vector_rankings(project, &eval_query.query, project_dir, candidates as u64, None)
+                    .await?;
+                (rankings.file_keys, rankings.symbol_keys)
+            }
+        };
+        let keyword_keys: Vec<String> = keyword_index::search(project_dir, &eval_query.query, candidates)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        let hybrid_keys: Vec<String> = search_service
+            .fuse_rankings(project, &eval_query.query, project_dir, candidates, vector_keys.clone(), symbol_vector_keys, None)
+            .into_iter()
+            .map(|hit| hit.key)
+            .collect();
+
+        let strategies = [("vector", vector_keys), ("keyword", keyword_keys), ("hybrid", hybrid_keys)]
+            .into_iter()
+            .map(|(name, ranked)| {
+                let metrics = RankingMetrics::score(&ranked, &relevant_set, k);
+                let top = ranked.into_iter().take(k).collect();
+                (name.to_string(), StrategyResult { metrics, top })
+            })
+            .collect();
+
+        queries.push(QueryEval {
+            query: eval_query.query.clone(),
+            relevant,
+            unknown_relevant,
+            strategies,
+        });
+    }
+
+    let summary = ["vector", "keyword", "hybrid"]
+        .into_iter()
+        .map(|name| {
+            let all: Vec<RankingMetrics> = queries.iter().map(|q| q.strategies[name].metrics).collect();
+            (name.to_string(), RankingMetrics::mean(&all))
+        })
+        .collect();
+
+    let created_at = Utc::now();
+    let run = EvalRun {
+        id: created_at.format("%Y%m%d-%H%M%S%3f").to_string(),
+        project: project_name.to_string(),
+        created_at,
+        k,
+        embedder: options.embedder,
+        embedding_config: project.embedding_config.clone(),
+        hybrid_search: project.hybrid_search.clone(),
+        default_use_yaml: project.default_use_yaml,
+        summary,
+        queries,
+    };
+    save_run(project_dir, &run)?;
+    println!("Eval run {} for {}: {:?}", run.id, project_name, run.summary);
+    Ok(run)
+}
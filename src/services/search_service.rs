@@ -1,12 +1,19 @@
 // src/services/search_service.rs
 use crate::models::Project;
 use crate::services::embedding_service::EmbeddingService;
-use crate::services::qdrant_service::QdrantService;
+use crate::services::vector_store::payload::{FilterCondition, FilterTerm};
+use crate::services::vector_store::{self, project_collection_name, FileSearchHit, PayloadFilter, SymbolHit};
 use crate::services::llm_service::{LlmService, LlmServiceConfig}; // Import LlmServiceConfig
 use crate::services::file::FileService;
 use crate::services::project_service::ProjectService;
+use crate::services::rank_fusion::{reciprocal_rank_fusion, FusedHit, RankedList, SearchSource, SourceRanks};
+use crate::services::yaml::{keyword_index, symbol_index};
 use crate::models::QueryData;
-use std::env;
+use crate::services::utils::llm_json::parse_llm_json;
+use crate::services::rerank_service::{RerankScore, RerankService, RERANK_CANDIDATES};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Debug, Clone)] // Add Clone and Debug for use in agent service
 pub struct SearchResult {
@@ -15,9 +22,139 @@ pub struct SearchResult {
     pub file_description: Option<String>, // Include file description
     pub score: f32,
     pub embedding: Option<Vec<f32>>,
+    pub matched_symbols: Vec<SymbolHit>, // Functions/methods/data structures that matched, best first
+    pub ranks: SourceRanks, // Rank in each hybrid search source that found the file
+    pub rerank: Option<RerankScore>, // Set when the LLM re-rank stage ordered the results
 }
 
 pub struct SearchService;
+
+/// One page of search results plus filters, as taken by the JSON search API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchOptions {
+    pub query: String,
+    /// 1-based
+    #[serde(default = "default_page")]
+    pub page: usize,
+    #[serde(default = "default_page_size")]
+    pub page_size: usize,
+    /// Only files under this directory, or this file (absolute, or relative to the source dir).
+    /// Shorthand for a `path:` filter term.
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Only files with these extensions, e.g. `["rs", "ts"]`. Shorthand for an `ext:` filter term.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Filter expression over file metadata, e.g. `"path:src/services/** ext:rs -path:**/tests/**"`
+    #[serde(default)]
+    pub filter: Option<PayloadFilter>,
+    #[serde(default)]
+    pub rerank: bool,
+    /// Also ask the LLM for suggested files and BM25 keywords
+    #[serde(default)]
+    pub llm_analysis: bool,
+}
+
+fn default_page() -> usize {
+    1
+}
+
+fn default_page_size() -> usize {
+    10
+}
+
+/// Maximum page size accepted by `search_page`.
+const MAX_PAGE_SIZE: usize = 100;
+
+pub struct SearchPage {
+    /// Page and page size actually used, after clamping
+    pub page: usize,
+    pub page_size: usize,
+    pub results: Vec<SearchResult>,
+    /// Matching results across all pages (among the retrieved candidates)
+    pub total: usize,
+    pub llm_analysis_raw: Option<String>,
+}
+
+/// `prefix` as an absolute path under the project's source dir.
+fn source_path_prefix(project: &Project, prefix: &str) -> String {
+    let prefix = prefix.trim();
+    if prefix.starts_with(&project.source_dir) {
+        prefix.to_string()
+    } else {
+        Path::new(&project.source_dir).join(prefix.trim_start_matches('/')).to_string_lossy().to_string()
+    }
+}
+
+impl SearchOptions {
+    /// `filter` with the `path_prefix` and `languages` shorthands added as terms, so they are
+    /// applied during retrieval like any other filter.
+    fn payload_filter(&self, project: &Project) -> PayloadFilter {
+        let mut filter = self.filter.clone().unwrap_or_default();
+        let prefix = self.path_prefix.as_deref().map(str::trim).unwrap_or_default();
+        let relative = prefix.strip_prefix(project.source_dir.as_str()).unwrap_or(prefix).trim_matches('/');
+        if !relative.is_empty() {
+            filter.terms.push(FilterTerm { negated: false, condition: FilterCondition::Path(vec![relative.to_string()]) });
+        }
+        let extensions: Vec<String> = self
+            .languages
+            .iter()
+            .map(|language| language.trim().trim_start_matches('.').to_lowercase())
+            .filter(|extension| !extension.is_empty())
+            .collect();
+        if !extensions.is_empty() {
+            filter.terms.push(FilterTerm { negated: false, condition: FilterCondition::Extension(extensions) });
+        }
+        filter
+    }
+}
+
+/// Reads the source of each result into `file_content`.
+fn load_file_contents(project: &Project, results: &mut [SearchResult]) {
+    let file_service = FileService {};
+    for result in results {
+        result.file_content = file_service.read_specific_file(project, &result.file_path).unwrap_or_default();
+    }
+}
+
+/// The JSON block `search_project` asks the LLM for alongside search results.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LlmSearchAnalysis {
+    /// Whether the vector results look relevant to the query, and why
+    pub accurate_vector_results: String,
+    /// Files (relative to the source dir) needed to answer the query
+    pub suggested_files: Vec<String>,
+    /// Space separated keywords for a broader BM25F search
+    pub bm25_keywords: String,
+}
+
+/// Parses the reply to the `search_project` analysis prompt.
+pub fn parse_llm_search_analysis(llm_analysis_raw: String) -> Result<LlmSearchAnalysis, String> {
+    parse_llm_json(llm_analysis_raw)
+}
+
+/// Vector search results for a query, with whole-file and symbol points ranked separately.
+pub struct VectorRankings {
+    pub file_keys: Vec<String>,
+    pub symbol_keys: Vec<String>,
+    pub hits: Vec<FileSearchHit>,
+}
+
+/// Words in a query that look like code identifiers: `snake_case`, `Type::method`,
+/// `obj.method` or camelCase/PascalCase with an inner capital.
+fn query_identifiers(query_text: &str) -> Vec<&str> {
+    query_text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':' || c == '.'))
+        .map(|word| word.trim_matches(|c: char| c == '.' || c == ':'))
+        .filter(|word| {
+            word.contains('_')
+                || word.contains("::")
+                || word.contains('.')
+                || (word.chars().skip(1).any(|c| c.is_uppercase()) && word.chars().any(|c| c.is_lowercase()))
+        })
+        .collect()
+}
 // src/services/search_service.rs
 
 impl SearchService {
@@ -25,63 +162,279 @@ impl SearchService {
         Self {}
     }
 
-    pub async fn search_project(
+    /// Vector, keyword and symbol retrieval merged with reciprocal rank fusion, using the
+    /// project's `hybrid_search` settings. `score` on each result is the fused score and
+    /// `ranks` records where the file placed in every source that found it. With a `filter`,
+    /// only files whose metadata matches it are returned. `file_content` is left empty; callers
+    /// that need it read it for the results they keep.
+    pub async fn hybrid_search(
         &self,
-        project: &mut Project,
+        project: &Project,
         query_text: &str,
-        output_dir: Option<&std::path::PathBuf>,
-        num_results: u64, // Add num_results parameter
-        llm_analysis: bool, // Add llm_analysis parameter
-    ) -> Result<(Vec<SearchResult>, String), String> {
+        project_dir: &Path,
+        num_results: u64,
+        filter: Option<&PayloadFilter>,
+    ) -> Result<Vec<SearchResult>, String> {
+        let config = &project.hybrid_search;
+        let candidates = config.candidates_per_source.max(num_results);
+
+        let vector = self.vector_rankings(project, query_text, project_dir, candidates, filter).await?;
+        let fused = self.fuse_rankings(
+            project,
+            query_text,
+            project_dir,
+            candidates as usize,
+            vector.file_keys,
+            vector.symbol_keys,
+            filter,
+        );
+        let vector_hits = vector.hits;
 
-        // 2. Generate embedding
-        let embedding_service = EmbeddingService::new();
-        let query_embedding = match embedding_service
-            .generate_embedding(query_text, Some(1536))
+        let search_results = fused
+            .into_iter()
+            .take(num_results as usize)
+            .map(|hit| {
+                let vector_hit = vector_hits.iter().find(|v| v.file_path == hit.key);
+                SearchResult {
+                    file_content: String::new(),
+                    // Use existing file description if available
+                    file_description: project.file_descriptions.get(&hit.key).cloned(),
+                    score: hit.score,
+                    embedding: vector_hit.and_then(|v| v.embedding.clone()),
+                    matched_symbols: vector_hit.map(|v| v.symbols.clone()).unwrap_or_default(),
+                    ranks: hit.ranks,
+                    rerank: None,
+                    file_path: hit.key,
+                }
+            })
+            .collect();
+
+        Ok(search_results)
+    }
+
+    /// Embeds the query and ranks the project's whole-file points and symbol points separately.
+    pub async fn vector_rankings(
+        &self,
+        project: &Project,
+        query_text: &str,
+        project_dir: &Path,
+        candidates: u64,
+        filter: Option<&PayloadFilter>,
+    ) -> Result<VectorRankings, String> {
+        let embedding_service = EmbeddingService::for_project(project_dir);
+        let query_embedding = embedding_service
+            .generate_embedding(query_text, &project.embedding_config)
             .await
-        {
-            Ok(embedding) => embedding,
-            Err(e) => return Err(e.to_string()),
-        };
+            .map_err(|e| e.to_string())?;
+
+        let vector_store = vector_store::open_project_store(project, project_dir)
+            .await
+            .map_err(|e| format!("Failed to open vector store: {}", e))?;
+        let hits = vector_store
+            .search_similar_files(&project_collection_name(project), query_embedding, candidates, false, filter)
+            .await
+            .map_err(|e| e.to_string())?;
 
-        // 3. Search for similar files using vector search
-        let qdrant_server_url =
-            env::var("QDRANT_SERVER_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
-        let qdrant_service = match QdrantService::new(&qdrant_server_url, 1536).await {
-            Ok(service) => service,
-            Err(e) => return Err(format!("Failed to connect to Qdrant: {}", e)),
+        let ranked_keys = |mut ranked: Vec<(&str, f32)>| -> Vec<String> {
+            ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            ranked.into_iter().map(|(path, _)| path.to_string()).collect()
         };
+        let file_keys = ranked_keys(
+            hits.iter()
+                .filter_map(|hit| hit.file_score.map(|score| (hit.file_path.as_str(), score)))
+                .collect(),
+        );
+        let symbol_keys = ranked_keys(
+            hits.iter()
+                .filter_map(|hit| hit.symbols.first().map(|symbol| (hit.file_path.as_str(), symbol.score)))
+                .collect(),
+        );
 
-        let similar_files_with_score = match qdrant_service
-            .search_similar_files(&project.name, query_embedding, num_results, false) // Use num_results
+        Ok(VectorRankings { file_keys, symbol_keys, hits })
+    }
+
+    /// Fuses vector rankings (whole-file and symbol points, best first) with the keyword
+    /// index and files defining a symbol the query names, using the project's RRF settings.
+    /// The vector keys are expected to be filtered already; `filter` is applied to the others.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fuse_rankings(
+        &self,
+        project: &Project,
+        query_text: &str,
+        project_dir: &Path,
+        candidates: usize,
+        file_vector_keys: Vec<String>,
+        symbol_vector_keys: Vec<String>,
+        filter: Option<&PayloadFilter>,
+    ) -> Vec<FusedHit> {
+        let config = &project.hybrid_search;
+        let mut keyword_keys: Vec<String> = keyword_index::search(project_dir, query_text, candidates)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        // Files defining a symbol the query names outright rank ahead of symbol vector matches
+        let mut symbol_keys = symbol_index::files_defining(project_dir, &query_identifiers(query_text));
+
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            let mut matches: HashMap<String, bool> = HashMap::new();
+            let mut keep = |key: &String| {
+                *matches
+                    .entry(key.clone())
+                    .or_insert_with(|| filter.matches_file(project, key))
+            };
+            keyword_keys.retain(&mut keep);
+            symbol_keys.retain(&mut keep);
+        }
+        symbol_keys.extend(symbol_vector_keys);
+
+        reciprocal_rank_fusion(
+            &[
+                RankedList { source: SearchSource::Vector, weight: config.vector_weight, keys: file_vector_keys },
+                RankedList { source: SearchSource::Keyword, weight: config.keyword_weight, keys: keyword_keys },
+                RankedList { source: SearchSource::Symbol, weight: config.symbol_weight, keys: symbol_keys },
+            ],
+            config.rrf_k,
+        )
+    }
+
+    /// "More like this": nearest files to `file_path` using the vector already stored for it,
+    /// so nothing is embedded. The file itself is excluded; with `within_dir` only files
+    /// under that directory (absolute, or relative to the source dir) are returned.
+    pub async fn similar_to_file(
+        &self,
+        project: &Project,
+        file_path: &str,
+        project_dir: &Path,
+        within_dir: Option<&str>,
+        num_results: u64,
+    ) -> Result<Vec<SearchResult>, String> {
+        let metadata = project
+            .embeddings
+            .get(file_path)
+            .ok_or_else(|| format!("{} has no stored embedding", file_path))?;
+
+        let collection_name = project_collection_name(project);
+        let vector_store = vector_store::open_project_store(project, project_dir)
             .await
-        {
-            Ok(files) => files,
-            Err(e) => return Err(e.to_string()),
-        };
+            .map_err(|e| format!("Failed to open vector store: {}", e))?;
+        let point = vector_store
+            .get_point(&collection_name, &metadata.vector_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Stored vector for {} not found; try reconciling embeddings", file_path))?;
 
-        // Initialize FileService
-        let file_service = FileService {};
-
-        // Map the search results with file data and descriptions
-        let mut search_results: Vec<SearchResult> = Vec::new();
-        for (file_path, _, score, embedding) in similar_files_with_score {
-            let file_content = file_service
-                .read_specific_file(project, &file_path)
-                .unwrap_or_default();
-
-            // Use existing file description if available
-            let file_description = project.file_descriptions.get(&file_path).cloned();
-
-            search_results.push(SearchResult {
-                file_path,
-                file_content,
-                file_description,
-                score,
-                embedding,
-            });
+        let dir_prefix = within_dir
+            .filter(|dir| !dir.trim().is_empty())
+            .map(|dir| format!("{}/", source_path_prefix(project, dir.trim_end_matches('/'))));
+
+        // Ask for extra hits since the file itself and anything outside the directory are dropped
+        let oversample = if dir_prefix.is_some() { 4 } else { 1 };
+        let hits = vector_store
+            .search_similar_files(&collection_name, point.vector, num_results * oversample + 1, false, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let search_results = hits
+            .into_iter()
+            .filter(|hit| hit.file_path != file_path)
+            .filter(|hit| dir_prefix.as_ref().is_none_or(|prefix| hit.file_path.starts_with(prefix.as_str())))
+            .take(num_results as usize)
+            .enumerate()
+            .map(|(index, hit)| SearchResult {
+                file_content: String::new(),
+                file_description: project.file_descriptions.get(&hit.file_path).cloned(),
+                score: hit.score,
+                embedding: None,
+                matched_symbols: hit.symbols,
+                ranks: SourceRanks { vector: Some(index + 1), ..Default::default() },
+                rerank: None,
+                file_path: hit.file_path,
+            })
+            .collect();
+
+        Ok(search_results)
+    }
+
+    /// Hybrid search with metadata filters and paging; optionally LLM re-ranked. `total`
+    /// counts matches among the retrieved candidates rather than the whole project. Only
+    /// the returned page has its `file_content` read.
+    pub async fn search_page(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        options: &SearchOptions,
+    ) -> Result<SearchPage, String> {
+        let page = options.page.max(1);
+        let page_size = options.page_size.clamp(1, MAX_PAGE_SIZE);
+        let filter = options.payload_filter(project);
+
+        let needed = page * page_size;
+        let pool = (needed as u64).max(project.hybrid_search.candidates_per_source);
+        let mut results: Vec<SearchResult> = self
+            .hybrid_search(project, &options.query, project_dir, pool, Some(&filter))
+            .await?;
+
+        if options.rerank {
+            let rest = results.split_off(results.len().min(RERANK_CANDIDATES as usize));
+            results = match RerankService::for_project(project_dir)
+                .rerank(project, project_dir, &options.query, results.clone())
+                .await
+            {
+                Ok(reranked) => reranked,
+                Err(e) => {
+                    eprintln!("LLM re-rank failed, keeping hybrid order: {}", e);
+                    results
+                }
+            };
+            results.extend(rest);
         }
 
+        let total = results.len();
+        let mut results: Vec<SearchResult> = results.into_iter().skip((page - 1) * page_size).take(page_size).collect();
+        load_file_contents(project, &mut results);
+
+        let llm_analysis_raw = if options.llm_analysis && !results.is_empty() {
+            Some(self.get_llm_analysis(&options.query, &results, project).await?)
+        } else {
+            None
+        };
+
+        Ok(SearchPage { page, page_size, results, total, llm_analysis_raw })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_project(
+        &self,
+        project: &mut Project,
+        query_text: &str,
+        project_dir: &Path,
+        output_dir: Option<&std::path::PathBuf>,
+        num_results: u64, // Add num_results parameter
+        llm_analysis: bool, // Add llm_analysis parameter
+        rerank: bool, // Re-order the top hybrid candidates with the LLM re-rank stage
+        filter: Option<&PayloadFilter>, // Only files whose metadata matches
+    ) -> Result<(Vec<SearchResult>, String), String> {
+
+        let mut search_results = if rerank {
+            let candidates = self
+                .hybrid_search(project, query_text, project_dir, num_results.max(RERANK_CANDIDATES), filter)
+                .await?;
+            let mut reranked = match RerankService::for_project(project_dir)
+                .rerank(project, project_dir, query_text, candidates.clone())
+                .await
+            {
+                Ok(reranked) => reranked,
+                Err(e) => {
+                    eprintln!("LLM re-rank failed, keeping hybrid order: {}", e);
+                    candidates
+                }
+            };
+            reranked.truncate(num_results as usize);
+            reranked
+        } else {
+            self.hybrid_search(project, query_text, project_dir, num_results, filter).await?
+        };
+        load_file_contents(project, &mut search_results);
 
         // Get LLM recommendations based on search results
         let llm_analysis = if llm_analysis {
@@ -165,4 +518,26 @@ impl SearchService {
 
         Ok(llm_response)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_prefix_and_languages_become_filter_terms() {
+        let project = Project { source_dir: "/repo".to_string(), ..Default::default() };
+        let options: SearchOptions = serde_json::from_value(serde_json::json!({
+            "query": "load",
+            "path_prefix": "/repo/src/services/",
+            "languages": [".RS", "ts"],
+            "filter": "-path:**/tests/**",
+        }))
+        .unwrap();
+        let filter = options.payload_filter(&project);
+        assert_eq!(filter, PayloadFilter::parse("-path:**/tests/** path:src/services ext:rs,ts").unwrap());
+
+        let unfiltered: SearchOptions = serde_json::from_value(serde_json::json!({ "query": "load", "path_prefix": " " })).unwrap();
+        assert!(unfiltered.payload_filter(&project).is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 // src/services/template/render_project_page.rs
-use crate::models::Project;
+use crate::models::{EmbeddingProviderKind, Project, VectorBackend};
 use super::TemplateService;
 use crate::shared;
 
@@ -12,6 +12,29 @@ impl TemplateService {
         query_value: &str,
         _query_id: &str,
     ) -> String {
+        let migration_status = match &project.pending_embedding_migration {
+            Some(migration) => format!(
+                r#"<p class="migration-status">Re-embedding with {} ({} dims) in the background; search uses the current embeddings until it finishes.</p>"#,
+                migration.config.model, migration.config.dimensions
+            ),
+            None => String::new(),
+        };
+        let hybrid = &project.hybrid_search;
+        let hybrid_search_fields = format!(
+            r#"<fieldset class="form-group">
+                                <legend>Hybrid Search (reciprocal rank fusion)</legend>
+                                <label for="rrf_k">k:</label>
+                                <input type="number" id="rrf_k" name="rrf_k" value="{}" min="1" step="any">
+                                <label for="vector_weight">Vector weight:</label>
+                                <input type="number" id="vector_weight" name="vector_weight" value="{}" min="0" step="any">
+                                <label for="keyword_weight">Keyword weight:</label>
+                                <input type="number" id="keyword_weight" name="keyword_weight" value="{}" min="0" step="any">
+                                <label for="symbol_weight">Symbol weight:</label>
+                                <input type="number" id="symbol_weight" name="symbol_weight" value="{}" min="0" step="any">
+                            </fieldset>"#,
+            hybrid.rrf_k, hybrid.vector_weight, hybrid.keyword_weight, hybrid.symbol_weight
+        );
+
         format!(
             r#"
         <!DOCTYPE html>
@@ -63,6 +86,35 @@ impl TemplateService {
                                 <label for="yaml_model">YAML Model (optional):</label> <!-- NEW FIELD HERE -->
                                 <input type="text" id="yaml_model" name="yaml_model" value="{}">
                             </div>
+                            <div class="form-group">
+                                <label for="vector_backend">Vector Store:</label>
+                                <select name="vector_backend" id="vector_backend">
+                                    <option value="qdrant" {}> Qdrant</option>
+                                    <option value="local" {}> Local (embedded)</option>
+                                </select>
+                            </div>
+                            <div class="form-group">
+                                <label for="embedding_provider">Embedding Provider:</label>
+                                <select name="embedding_provider" id="embedding_provider">
+                                    <option value="open_ai" {}> OpenAI</option>
+                                    <option value="open_ai_compatible" {}> OpenAI-compatible server</option>
+                                    <option value="local_hashing" {}> Local hashing (offline)</option>
+                                </select>
+                            </div>
+                            <div class="form-group">
+                                <label for="embedding_model">Embedding Model:</label>
+                                <input type="text" id="embedding_model" name="embedding_model" value="{}">
+                            </div>
+                            <div class="form-group">
+                                <label for="embedding_base_url">Embedding Base URL (OpenAI-compatible only):</label>
+                                <input type="text" id="embedding_base_url" name="embedding_base_url" value="{}" placeholder="http://localhost:11434/v1">
+                            </div>
+                            <div class="form-group">
+                                <label for="embedding_dimensions">Embedding Dimensions:</label>
+                                <input type="number" id="embedding_dimensions" name="embedding_dimensions" value="{}" min="1">
+                                {}
+                            </div>
+                            {}
                             <div class="form-group">
                                 <label for="default_use_yaml">Default Use YAML:</label>
                                 <input type="checkbox" id="default_use_yaml" name="default_use_yaml" {} value="true">
@@ -71,22 +123,43 @@ impl TemplateService {
                                 <label for="git_integration_enabled">Enable Git Integration:</label>
                                 <input type="checkbox" id="git_integration_enabled" name="git_integration_enabled" {} value="true">
                             </div>
+                            <div class="form-group">
+                                <label for="chat_memory_enabled">Agent Memory (search past chats):</label>
+                                <input type="checkbox" id="chat_memory_enabled" name="chat_memory_enabled" {} value="true">
+                            </div>
+                            <div class="form-group">
+                                <label for="rust_skeleton_only">Rust YAML from parser only (no LLM):</label>
+                                <input type="checkbox" id="rust_skeleton_only" name="rust_skeleton_only" {} value="true">
+                            </div>
                             <button type="submit">Update Settings</button>
                             <div class="form-group">
                                 <button type="button" onclick="runClustering()">Run Clustering</button>
                             </div>
+                            <div class="form-group">
+                                <button type="button" onclick="runReconcile()">Reconcile Embeddings</button>
+                            </div>
                         </form>
                         <div class="form-group">
                             <a href="/projects/{}/git-env" class="button">Configure Git Author/Email</a>
                         </div>
+                        <div class="form-group">
+                            <a href="/api/export/{}" class="button">Export Vectors (JSON)</a>
+                        </div>
                     </div>
 
                     <p>Source Directory: {}</p>
 
+                    <div id="indexing-status" class="indexing-status" style="display: none;"></div>
+                    <details id="jobs-panel" class="jobs-panel">
+                        <summary>Background Jobs</summary>
+                        <div id="jobs-list"></div>
+                    </details>
+
                     <!-- Search Form -->
                     <div class="search-form">
                         <form action="/projects/{}" method="post">
                             <textarea name="q" placeholder="Enter your query...">{}</textarea>
+                            <label><input type="checkbox" name="rerank" value="true"> Re-rank with LLM</label>
                             <button type="submit">Submit</button>
                         </form>
                     </div>
@@ -120,8 +193,21 @@ impl TemplateService {
             if project.provider == "anthropic" { "selected" } else { "" },
             project.specific_model.as_deref().unwrap_or(""),
             project.yaml_model.as_deref().unwrap_or(""), // Pass the new yaml_model value
+            if project.vector_backend == VectorBackend::Qdrant { "selected" } else { "" },
+            if project.vector_backend == VectorBackend::Local { "selected" } else { "" },
+            if project.embedding_config.provider == EmbeddingProviderKind::OpenAi { "selected" } else { "" },
+            if project.embedding_config.provider == EmbeddingProviderKind::OpenAiCompatible { "selected" } else { "" },
+            if project.embedding_config.provider == EmbeddingProviderKind::LocalHashing { "selected" } else { "" },
+            project.embedding_config.model,
+            project.embedding_config.base_url.as_deref().unwrap_or(""),
+            project.embedding_config.dimensions,
+            migration_status,
+            hybrid_search_fields,
             if project.default_use_yaml { "checked" } else { "" },
             if project.git_integration_enabled { "checked" } else { "" },
+            if project.chat_memory_enabled { "checked" } else { "" },
+            if project.rust_skeleton_only { "checked" } else { "" },
+            project.name,
             project.name,
             project.source_dir,
             project.name,
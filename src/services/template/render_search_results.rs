@@ -18,12 +18,47 @@ impl TemplateService {
             query_text
         );
 
-        for SearchResult { file_path, score, .. } in similar_files {
+        for SearchResult { file_path, score, matched_symbols, ranks, rerank, .. } in similar_files {
+            let symbols_html = if matched_symbols.is_empty() {
+                String::new()
+            } else {
+                let items: String = matched_symbols
+                    .iter()
+                    .map(|symbol| {
+                        let name = match &symbol.parent_symbol {
+                            Some(parent) => format!("{}::{}", parent, symbol.name),
+                            None => symbol.name.clone(),
+                        };
+                        let lines = match (symbol.start_line, symbol.end_line) {
+                            (Some(start), Some(end)) => format!(" lines {}&ndash;{}", start, end),
+                            _ => String::new(),
+                        };
+                        format!("<li>{} <em>({}{})</em> (Score: {:.4})</li>", name, symbol.kind, lines, symbol.score)
+                    })
+                    .collect();
+                format!(r#"<ul class="matched-symbols">{}</ul>"#, items)
+            };
+            let ranks_description = ranks.describe();
+            let ranks_html = if ranks_description.is_empty() {
+                String::new()
+            } else {
+                format!(r#"<p class="source-ranks">Found by: {}</p>"#, ranks_description)
+            };
+            let rerank_html = match rerank {
+                Some(rerank) => format!(
+                    r#"<p class="rerank">LLM relevance: {:.2} &mdash; {}</p>"#,
+                    rerank.relevance, html_escape::encode_text(&rerank.reason)
+                ),
+                None => String::new(),
+            };
             search_results_html.push_str(&format!(
                 r#"<div class="result-file">
                 <h3>{} (Score: {:.4})</h3>
+                {}
+                {}
+                {}
             </div>"#,
-                file_path, score
+                file_path, score, ranks_html, rerank_html, symbols_html
             ));
         }
 
@@ -0,0 +1,162 @@
+// src/services/tokenizer.rs
+
+/// Words too common in queries and summaries to say anything about a file.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "has",
+    "have", "how", "if", "in", "into", "is", "it", "its", "of", "on", "or", "so", "that", "the",
+    "then", "this", "to", "was", "we", "were", "what", "when", "where", "which", "who", "why",
+    "will", "with",
+];
+
+/// Code-aware analyzer shared by keyword indexing and querying, so both sides agree on terms.
+///
+/// Splits on anything that isn't alphanumeric (covering `snake_case`, `::` and `.` paths),
+/// then splits camelCase/PascalCase words, lowercases, drops stopwords and applies a light
+/// suffix-stripping stemmer. Identifiers made of several parts also yield the joined
+/// identifier, so an exact identifier match scores above a match on its parts alone.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for identifier in text.split(|c: char| !c.is_alphanumeric()).filter(|s| !s.is_empty()) {
+        let parts: Vec<String> = split_identifier_parts(identifier)
+            .into_iter()
+            .map(|part| part.to_lowercase())
+            .collect();
+        for part in &parts {
+            push_term(&mut tokens, part);
+        }
+        if parts.len() > 1 {
+            tokens.push(parts.concat());
+        }
+    }
+
+    // Whole snake_case / path identifiers, e.g. `get_blob_hash` -> `getblobhash`
+    for identifier in text.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let pieces: Vec<&str> = identifier.split('_').filter(|s| !s.is_empty()).collect();
+        if pieces.len() > 1 {
+            tokens.push(pieces.concat().to_lowercase());
+        }
+    }
+    tokens
+}
+
+fn push_term(tokens: &mut Vec<String>, word: &str) {
+    if word.chars().count() < 2 || STOPWORDS.contains(&word) {
+        return;
+    }
+    tokens.push(stem(word));
+}
+
+/// Splits `FileYamlData` into `File`, `Yaml`, `Data` and `HTTPServer` into `HTTP`, `Server`.
+fn split_identifier_parts(word: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (byte_index, current) = chars[i];
+        let previous = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|&(_, c)| c.is_lowercase());
+        let boundary = current.is_uppercase()
+            && (previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_is_lower));
+        if boundary {
+            parts.push(&word[start..byte_index]);
+            start = byte_index;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+fn has_vowel(word: &str) -> bool {
+    word.chars().any(|c| "aeiouy".contains(c))
+}
+
+/// Light stemmer: plurals, `-ing`, `-ed` and a trailing `e`, so "embeddings", "embedded"
+/// and "embed" or "parses", "parsed" and "parse" end up as the same term.
+pub fn stem(word: &str) -> String {
+    if !word.is_ascii() || word.len() <= 3 || word.chars().any(|c| c.is_ascii_digit()) {
+        return word.to_string();
+    }
+    let mut stem = word.to_string();
+
+    if stem.ends_with("ies") && stem.len() > 4 {
+        stem.truncate(stem.len() - 3);
+        stem.push('y');
+    } else if ["sses", "shes", "ches", "xes", "zes"].iter().any(|suffix| stem.ends_with(suffix)) {
+        stem.truncate(stem.len() - 2);
+    } else if stem.ends_with('s') && !stem.ends_with("ss") && !stem.ends_with("us") && !stem.ends_with("is") {
+        stem.truncate(stem.len() - 1);
+    }
+
+    for suffix in ["ing", "ed"] {
+        if stem.len() >= suffix.len() + 4 && stem.ends_with(suffix) {
+            let candidate = &stem[..stem.len() - suffix.len()];
+            if has_vowel(candidate) {
+                stem = candidate.to_string();
+                // "embedd" -> "embed", "gett" -> "get"
+                let bytes = stem.as_bytes();
+                let n = bytes.len();
+                if n >= 2 && bytes[n - 1] == bytes[n - 2] && !b"aeioulsz".contains(&bytes[n - 1]) {
+                    stem.truncate(n - 1);
+                }
+                break;
+            }
+        }
+    }
+
+    if stem.len() > 4 && stem.ends_with('e') {
+        stem.truncate(stem.len() - 1);
+    }
+    stem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(text: &str) -> Vec<String> {
+        let mut terms = tokenize(text);
+        terms.sort();
+        terms.dedup();
+        terms
+    }
+
+    #[test]
+    fn splits_snake_camel_and_pascal_case() {
+        assert_eq!(terms("get_blob_hash"), vec!["blob", "get", "getblobhash", "hash"]);
+        assert_eq!(terms("FileYamlData"), vec!["data", "file", "fileyamldata", "yaml"]);
+        assert_eq!(terms("parseHTTPResponse"), vec!["http", "pars", "parsehttpresponse", "respons"]);
+    }
+
+    #[test]
+    fn splits_paths() {
+        let tokens = terms("GitService::open_repository");
+        for expected in ["git", "servic", "open", "repository", "openrepository", "gitservice"] {
+            assert!(tokens.contains(&expected.to_string()), "missing {} in {:?}", expected, tokens);
+        }
+        assert!(terms("self.vector_store.upsert").contains(&"vectorstore".to_string()));
+    }
+
+    #[test]
+    fn query_words_match_identifier_parts() {
+        let index_terms = terms("get_blob_hash FileYamlData");
+        for query_term in tokenize("blob hash for the yaml data") {
+            assert!(index_terms.contains(&query_term), "{} not in {:?}", query_term, index_terms);
+        }
+    }
+
+    #[test]
+    fn stems_common_suffixes() {
+        assert_eq!(stem("embeddings"), stem("embed"));
+        assert_eq!(stem("embedded"), stem("embed"));
+        assert_eq!(stem("parses"), stem("parsed"));
+        assert_eq!(stem("caching"), stem("cache"));
+        assert_eq!(stem("hashes"), "hash");
+        assert_eq!(stem("string"), "string");
+        assert_eq!(stem("status"), "status");
+    }
+
+    #[test]
+    fn drops_stopwords_and_single_letters() {
+        assert_eq!(terms("how is the x of a file"), vec!["file"]);
+    }
+}
@@ -0,0 +1,55 @@
+// src/services/utils/llm_json.rs
+use crate::services::utils::html_utils::unescape_html;
+use serde::de::DeserializeOwned;
+
+/// The JSON object in an LLM reply: the first ```json fenced block, else the first plain
+/// fenced block, else the outermost `{...}` in the text.
+pub fn extract_json_block(reply: &str) -> Option<&str> {
+    let fenced = |marker: &str| {
+        let start = reply.find(marker)? + marker.len();
+        let end = reply[start..].find("```")?;
+        Some(reply[start..start + end].trim())
+    };
+    fenced("```json")
+        .or_else(|| fenced("```").filter(|block| block.starts_with('{') || block.starts_with('[')))
+        .or_else(|| {
+            let start = reply.find('{')?;
+            let end = reply.rfind('}')?;
+            (end > start).then(|| &reply[start..=end])
+        })
+}
+
+/// Deserializes the JSON object in an LLM reply (which may be HTML-escaped) into `T`.
+pub fn parse_llm_json<T: DeserializeOwned>(reply: String) -> Result<T, String> {
+    let unescaped = unescape_html(reply);
+    let json = extract_json_block(&unescaped).ok_or_else(|| format!("No JSON found in LLM reply: {}", unescaped))?;
+    serde_json::from_str(json).map_err(|e| format!("Failed to parse LLM JSON: {}\nFailed data: {}", e, json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_json_fence() {
+        let reply = "Sure:\n```json\n{\"a\": 1}\n```\nand ```{\"b\": 2}```";
+        assert_eq!(extract_json_block(reply), Some("{\"a\": 1}"));
+    }
+
+    #[test]
+    fn falls_back_to_plain_fence_or_braces() {
+        assert_eq!(extract_json_block("```\n{\"a\": 1}\n```"), Some("{\"a\": 1}"));
+        assert_eq!(extract_json_block("Here you go {\"a\": {\"b\": 2}} done"), Some("{\"a\": {\"b\": 2}}"));
+        assert_eq!(extract_json_block("no json here"), None);
+    }
+
+    #[test]
+    fn parses_escaped_reply() {
+        #[derive(serde::Deserialize)]
+        struct Reply {
+            keywords: String,
+        }
+        let reply: Reply = parse_llm_json("```json\n{&quot;keywords&quot;: &quot;a b&quot;}\n```".to_string()).unwrap();
+        assert_eq!(reply.keywords, "a b");
+    }
+}
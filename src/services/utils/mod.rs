@@ -1 +1,3 @@
-pub mod html_utils;
\ No newline at end of file
+pub mod html_utils;
+pub mod llm_json;
+pub mod text;
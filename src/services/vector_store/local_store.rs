@@ -0,0 +1,204 @@
+// src/services/vector_store/local_store.rs
+use super::{PayloadFilter, ScoredPoint, VectorPoint, VectorStore, VectorStoreResult};
+use crate::services::clustering_service::cosine_distance;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::sync::Mutex;
+
+/// Collections loaded from disk, keyed by their JSON file path.
+/// Every write goes straight back to disk, so this only saves re-parsing on reads.
+static COLLECTIONS: OnceLock<Mutex<HashMap<PathBuf, Vec<VectorPoint>>>> = OnceLock::new();
+
+fn collections() -> &'static Mutex<HashMap<PathBuf, Vec<VectorPoint>>> {
+    COLLECTIONS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// In-process vector store persisted as one JSON file per collection under
+/// `output/<project>/vector_store/`. Used when no Qdrant server is available.
+pub struct LocalVectorStore {
+    store_dir: PathBuf,
+}
+
+impl LocalVectorStore {
+    pub fn new(project_dir: &Path) -> Self {
+        LocalVectorStore {
+            store_dir: project_dir.join("vector_store"),
+        }
+    }
+
+    fn collection_path(&self, collection_name: &str) -> PathBuf {
+        self.store_dir.join(format!("{}.json", collection_name))
+    }
+
+    /// Makes sure the collection is present in the cache, loading it from disk if needed.
+    /// A cached collection whose file has been removed (e.g. project deleted) is dropped.
+    fn ensure_loaded(
+        cache: &mut HashMap<PathBuf, Vec<VectorPoint>>,
+        path: &Path,
+    ) -> VectorStoreResult<()> {
+        if !path.exists() {
+            cache.remove(path);
+            return Ok(());
+        }
+        if !cache.contains_key(path) {
+            let json = std::fs::read_to_string(path)?;
+            let points: Vec<VectorPoint> = serde_json::from_str(&json)?;
+            cache.insert(path.to_path_buf(), points);
+        }
+        Ok(())
+    }
+
+    fn persist(&self, path: &Path, points: &[VectorPoint]) -> VectorStoreResult<()> {
+        std::fs::create_dir_all(&self.store_dir)?;
+        // Write to a temp file first so a crash never leaves a half-written collection behind.
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string(points)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VectorStore for LocalVectorStore {
+    // Vectors are compared in memory, so the size is only enforced by the embedding model
+    async fn create_collection(&self, collection_name: &str, _vector_size: u64) -> VectorStoreResult<()> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        if !path.exists() {
+            self.persist(&path, &[])?;
+            cache.insert(path, Vec::new());
+        }
+        Ok(())
+    }
+
+    async fn delete_collection(&self, collection_name: &str) -> VectorStoreResult<()> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        cache.remove(&path);
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    async fn upsert_points(&self, collection_name: &str, points: Vec<VectorPoint>) -> VectorStoreResult<()> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        let stored = cache.entry(path.clone()).or_default();
+
+        for point in points {
+            match stored.iter_mut().find(|existing| existing.id == point.id) {
+                Some(existing) => *existing = point,
+                None => stored.push(point),
+            }
+        }
+
+        self.persist(&path, stored)
+    }
+
+    async fn delete_points(&self, collection_name: &str, point_ids: Vec<String>) -> VectorStoreResult<()> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        let Some(stored) = cache.get_mut(&path) else {
+            return Ok(());
+        };
+
+        let before = stored.len();
+        stored.retain(|point| !point_ids.contains(&point.id));
+
+        if stored.len() != before {
+            self.persist(&path, stored)?;
+        }
+        Ok(())
+    }
+
+    async fn get_point(&self, collection_name: &str, point_id: &str) -> VectorStoreResult<Option<VectorPoint>> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        Ok(cache
+            .get(&path)
+            .and_then(|points| points.iter().find(|point| point.id == point_id).cloned()))
+    }
+
+    async fn delete_stale_file_vectors(
+        &self,
+        collection_name: &str,
+        file_path: &str,
+        keep_ids: &[String],
+    ) -> VectorStoreResult<()> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        let Some(stored) = cache.get_mut(&path) else {
+            return Ok(()); // Nothing stored yet
+        };
+
+        let before = stored.len();
+        stored.retain(|point| {
+            point.payload.get("file_path").and_then(|v| v.as_str()) != Some(file_path) || keep_ids.contains(&point.id)
+        });
+
+        if stored.len() != before {
+            self.persist(&path, stored)?;
+        }
+        Ok(())
+    }
+
+    async fn search_points(
+        &self,
+        collection_name: &str,
+        query_vector: Vec<f32>,
+        limit: u64,
+        with_vectors: bool,
+        filter: Option<&PayloadFilter>,
+    ) -> VectorStoreResult<Vec<ScoredPoint>> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        let Some(stored) = cache.get(&path) else {
+            return Ok(Vec::new());
+        };
+
+        let mut scored: Vec<ScoredPoint> = stored
+            .iter()
+            .filter(|point| filter.is_none_or(|filter| filter.matches_payload(&point.payload)))
+            .map(|point| ScoredPoint {
+                score: 1.0 - cosine_distance(&query_vector, &point.vector),
+                payload: point.payload.clone(),
+                vector: if with_vectors { Some(point.vector.clone()) } else { None },
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit as usize);
+        Ok(scored)
+    }
+
+    async fn scroll_points(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        Ok(cache.get(&path).cloned().unwrap_or_default())
+    }
+
+    async fn scroll_payloads(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>> {
+        let path = self.collection_path(collection_name);
+        let mut cache = collections().lock().await;
+        Self::ensure_loaded(&mut cache, &path)?;
+        Ok(cache
+            .get(&path)
+            .map(|points| {
+                points
+                    .iter()
+                    .map(|point| VectorPoint { id: point.id.clone(), vector: Vec::new(), payload: point.payload.clone() })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,289 @@
+// src/services/vector_store/mod.rs
+pub mod local_store;
+pub mod payload;
+pub mod synced_collection;
+
+use crate::models::{Project, VectorBackend};
+use crate::services::qdrant_service::QdrantService;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::path::Path;
+use uuid::Uuid;
+
+pub use local_store::LocalVectorStore;
+pub use payload::{FileMetadata, PayloadFilter};
+
+pub type VectorStoreResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// A point as it is written to (or read back from) a collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorPoint {
+    pub id: String,
+    pub vector: Vec<f32>,
+    #[serde(default)]
+    pub payload: HashMap<String, Value>,
+}
+
+/// A search hit. `vector` is only filled in when the caller asked for vectors.
+#[derive(Debug, Clone)]
+pub struct ScoredPoint {
+    pub score: f32,
+    pub payload: HashMap<String, Value>,
+    pub vector: Option<Vec<f32>>,
+}
+
+/// A function, method or data structure point that matched a search.
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolHit {
+    pub name: String,
+    pub kind: String,
+    pub parent_symbol: Option<String>,
+    pub score: f32,
+    /// Where the symbol is in the source, for points embedded with a line range
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+/// Search hits rolled up to a single file. `score` is the best score of the file point
+/// or any of its symbol points; `file_score` is the file point's own score, if it matched.
+/// `embedding` is the file-level vector when requested.
+#[derive(Debug, Clone)]
+pub struct FileSearchHit {
+    pub file_path: String,
+    pub file_content: String,
+    pub score: f32,
+    pub file_score: Option<f32>,
+    pub embedding: Option<Vec<f32>>,
+    pub symbols: Vec<SymbolHit>,
+}
+
+/// `point_type` payload value for whole-file points. Points written before symbol
+/// chunks existed have no `point_type` and are treated as file points.
+pub const FILE_POINT: &str = "file";
+/// `point_type` payload value for function/method/data structure points.
+pub const SYMBOL_POINT: &str = "symbol";
+
+/// Symbol points are oversampled so that enough distinct files survive the roll-up.
+const SYMBOL_OVERSAMPLE: u64 = 4;
+
+/// Builds a whole-file point with a fresh id.
+pub fn file_point(file_path: &str, file_content: &str, embedding: Vec<f32>, metadata: &FileMetadata) -> VectorPoint {
+    let mut payload = HashMap::new();
+    payload.insert("file_path".to_string(), Value::from(file_path));
+    payload.insert("file_content".to_string(), Value::from(file_content));
+    payload.insert("point_type".to_string(), Value::from(FILE_POINT));
+    metadata.insert_into(&mut payload);
+
+    VectorPoint {
+        id: Uuid::new_v4().to_string(),
+        vector: embedding,
+        payload,
+    }
+}
+
+/// Reads a string field out of a point payload, returning an empty string if it is missing.
+pub fn payload_string(payload: &HashMap<String, Value>, key: &str) -> String {
+    payload
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn payload_line(payload: &HashMap<String, Value>, key: &str) -> Option<usize> {
+    payload.get(key).and_then(|v| v.as_u64()).map(|line| line as usize)
+}
+
+/// Storage backend for file embeddings. `QdrantService` talks to a Qdrant server,
+/// `LocalVectorStore` keeps everything in-process and persists it under the project output dir.
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Creates the collection if it does not exist yet.
+    async fn create_collection(&self, collection_name: &str, vector_size: u64) -> VectorStoreResult<()>;
+
+    /// Drops the collection and all of its points. Missing collections are not an error.
+    async fn delete_collection(&self, collection_name: &str) -> VectorStoreResult<()>;
+
+    async fn upsert_points(&self, collection_name: &str, points: Vec<VectorPoint>) -> VectorStoreResult<()>;
+
+    async fn delete_points(&self, collection_name: &str, point_ids: Vec<String>) -> VectorStoreResult<()>;
+
+    /// Fetches one point with its payload and vector, or `None` if it doesn't exist.
+    async fn get_point(&self, collection_name: &str, point_id: &str) -> VectorStoreResult<Option<VectorPoint>>;
+
+    /// Deletes every point whose `file_path` payload matches `file_path`.
+    async fn delete_file_vectors(&self, collection_name: &str, file_path: &str) -> VectorStoreResult<()> {
+        self.delete_stale_file_vectors(collection_name, file_path, &[]).await
+    }
+
+    /// Deletes the points of `file_path` other than `keep_ids`, e.g. the ones a re-embedding
+    /// just stored. Lets callers write new points before dropping the old ones.
+    async fn delete_stale_file_vectors(
+        &self,
+        collection_name: &str,
+        file_path: &str,
+        keep_ids: &[String],
+    ) -> VectorStoreResult<()>;
+
+    /// Cosine similarity search, best match first. With a `filter`, only points whose
+    /// metadata matches it are returned (up to `limit` of them).
+    async fn search_points(
+        &self,
+        collection_name: &str,
+        query_vector: Vec<f32>,
+        limit: u64,
+        with_vectors: bool,
+        filter: Option<&PayloadFilter>,
+    ) -> VectorStoreResult<Vec<ScoredPoint>>;
+
+    /// Returns every point in the collection together with its payload and vector,
+    /// paging through the whole collection rather than relying on a similarity search.
+    async fn scroll_points(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>>;
+
+    /// Like `scroll_points`, but without fetching vectors (`vector` is left empty), for
+    /// callers that only look at ids and payloads.
+    async fn scroll_payloads(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>>;
+
+    /// Like `scroll_points`, but only the whole-file points (no symbol chunks).
+    async fn scroll_file_points(&self, collection_name: &str) -> VectorStoreResult<Vec<VectorPoint>> {
+        let points = self.scroll_points(collection_name).await?;
+        Ok(points
+            .into_iter()
+            .filter(|point| payload_string(&point.payload, "point_type") != SYMBOL_POINT)
+            .collect())
+    }
+
+    /// Replaces any existing points for `file_path` (file and symbol points) with a single
+    /// new file point and returns its id.
+    async fn store_file_embedding(
+        &self,
+        collection_name: &str,
+        file_path: &str,
+        file_content: &str,
+        embedding: Vec<f32>,
+        metadata: &FileMetadata,
+    ) -> VectorStoreResult<String> {
+        // The old points stay searchable until the new one is stored
+        let point = file_point(file_path, file_content, embedding, metadata);
+        let point_id = point.id.clone();
+        self.upsert_points(collection_name, vec![point]).await?;
+        self.delete_stale_file_vectors(collection_name, file_path, std::slice::from_ref(&point_id)).await?;
+
+        println!("Upsert successful for file: {}", file_path);
+
+        Ok(point_id)
+    }
+
+    /// Searches file and symbol points and rolls the hits up per file, best file first.
+    /// `filter` restricts the search to files whose payload metadata matches it.
+    async fn search_similar_files(
+        &self,
+        collection_name: &str,
+        query_embedding: Vec<f32>,
+        limit: u64,
+        return_embeddings: bool,
+        filter: Option<&PayloadFilter>,
+    ) -> VectorStoreResult<Vec<FileSearchHit>> {
+        let points = self
+            .search_points(
+                collection_name,
+                query_embedding,
+                limit * SYMBOL_OVERSAMPLE,
+                return_embeddings,
+                filter.filter(|filter| !filter.is_empty()),
+            )
+            .await?;
+
+        let mut hits: Vec<FileSearchHit> = Vec::new();
+        // Where to find the content of files that so far only matched through symbols
+        let mut file_point_ids: HashMap<String, String> = HashMap::new();
+        for point in points {
+            let file_path = payload_string(&point.payload, "file_path");
+            let index = match hits.iter().position(|hit| hit.file_path == file_path) {
+                Some(index) => index,
+                None => {
+                    hits.push(FileSearchHit {
+                        file_path: file_path.clone(),
+                        file_content: String::new(),
+                        score: point.score,
+                        file_score: None,
+                        embedding: None,
+                        symbols: Vec::new(),
+                    });
+                    hits.len() - 1
+                }
+            };
+            let hit = &mut hits[index];
+            hit.score = hit.score.max(point.score);
+
+            if payload_string(&point.payload, "point_type") == SYMBOL_POINT {
+                file_point_ids
+                    .entry(file_path.clone())
+                    .or_insert_with(|| payload_string(&point.payload, "file_point_id"));
+                let parent_symbol = payload_string(&point.payload, "parent_symbol");
+                hit.symbols.push(SymbolHit {
+                    name: payload_string(&point.payload, "symbol_name"),
+                    kind: payload_string(&point.payload, "symbol_kind"),
+                    parent_symbol: if parent_symbol.is_empty() { None } else { Some(parent_symbol) },
+                    score: point.score,
+                    start_line: payload_line(&point.payload, "start_line"),
+                    end_line: payload_line(&point.payload, "end_line"),
+                });
+            } else {
+                hit.file_score = Some(point.score);
+                hit.file_content = payload_string(&point.payload, "file_content");
+                hit.embedding = point.vector;
+            }
+        }
+
+        // Points come back best first, so symbols are already sorted; only files need re-sorting
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(limit as usize);
+
+        // Symbol points only carry the symbol's text; give those files their embedded content
+        for hit in hits.iter_mut().filter(|hit| hit.file_score.is_none()) {
+            let file_point_id = file_point_ids.get(&hit.file_path).filter(|id| !id.is_empty());
+            if let Some(file_point_id) = file_point_id {
+                match self.get_point(collection_name, file_point_id).await {
+                    Ok(Some(point)) => hit.file_content = payload_string(&point.payload, "file_content"),
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Failed to load file point for {}: {}", hit.file_path, e),
+                }
+            }
+            // Older symbol points have no file_point_id; fall back to the source on disk
+            if hit.file_content.is_empty() {
+                hit.file_content = std::fs::read_to_string(&hit.file_path).unwrap_or_default();
+            }
+        }
+        Ok(hits)
+    }
+}
+
+/// Name of the collection currently holding a project's file embeddings.
+pub fn project_collection_name(project: &Project) -> String {
+    project
+        .vector_collection
+        .clone()
+        .unwrap_or_else(|| format!("project_{}", project.name))
+}
+
+/// Opens the vector store selected in the project's settings.
+/// `project_dir` is the project's output directory (e.g. `output/<project>`).
+pub async fn open_project_store(
+    project: &Project,
+    project_dir: &Path,
+) -> VectorStoreResult<Box<dyn VectorStore>> {
+    match project.vector_backend {
+        VectorBackend::Qdrant => {
+            let qdrant_server_url = env::var("QDRANT_SERVER_URL")
+                .unwrap_or_else(|_| "http://localhost:6334".to_string());
+            let qdrant_service = QdrantService::new(&qdrant_server_url).await?;
+            Ok(Box::new(qdrant_service))
+        }
+        VectorBackend::Local => Ok(Box::new(LocalVectorStore::new(project_dir))),
+    }
+}
@@ -0,0 +1,405 @@
+// src/services/vector_store/payload.rs
+use crate::models::Project;
+use git2::{ObjectType, Oid};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Per-file metadata stored on every point (file and symbol) so searches can be filtered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMetadata {
+    /// Path relative to the project's source dir, e.g. `src/services/mod.rs`
+    pub relative_path: String,
+    /// Directory part of `relative_path`; empty for files at the root
+    pub relative_dir: String,
+    /// Lower-case extension without the dot
+    pub extension: String,
+    pub language: String,
+    /// Whether the embedded content is the YAML summary rather than the source
+    pub is_yaml: bool,
+    pub line_count: u64,
+    pub git_blob_hash: Option<String>,
+}
+
+/// Language name for a file extension; unknown extensions are their own language.
+fn language_for_extension(extension: &str) -> String {
+    match extension {
+        "rs" => "rust",
+        "py" => "python",
+        "ts" | "tsx" => "typescript",
+        "js" | "jsx" | "mjs" => "javascript",
+        "md" => "markdown",
+        "yml" | "yaml" => "yaml",
+        "sh" => "shell",
+        other => other,
+    }
+    .to_string()
+}
+
+fn relative_to_source<'a>(project: &Project, source_path: &'a str) -> &'a str {
+    source_path
+        .strip_prefix(&project.source_dir)
+        .map(|path| path.trim_start_matches('/'))
+        .unwrap_or(source_path)
+}
+
+fn count_lines(content: &[u8]) -> u64 {
+    String::from_utf8_lossy(content).lines().count() as u64
+}
+
+impl FileMetadata {
+    /// Metadata for a source file given its path relative to the source dir and its bytes.
+    pub fn describe(relative_path: &str, content: &[u8], is_yaml: bool) -> Self {
+        FileMetadata {
+            line_count: count_lines(content),
+            git_blob_hash: if content.is_empty() {
+                None
+            } else {
+                Oid::hash_object(ObjectType::Blob, content).ok().map(|oid| oid.to_string())
+            },
+            ..Self::describe_path(relative_path, is_yaml)
+        }
+    }
+
+    /// The parts of the metadata that come from the path alone; no line count or blob hash.
+    fn describe_path(relative_path: &str, is_yaml: bool) -> Self {
+        let path = Path::new(relative_path);
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        FileMetadata {
+            relative_path: relative_path.to_string(),
+            relative_dir: path.parent().map(|dir| dir.to_string_lossy().to_string()).unwrap_or_default(),
+            language: language_for_extension(&extension),
+            extension,
+            is_yaml,
+            line_count: 0,
+            git_blob_hash: None,
+        }
+    }
+
+    /// Reads `source_path` (a key of `Project.embeddings`) and describes it. An unreadable
+    /// file still gets its path, extension and language.
+    pub fn for_file(project: &Project, source_path: &str) -> Self {
+        let content = std::fs::read(source_path).unwrap_or_default();
+        Self::describe(relative_to_source(project, source_path), &content, project.uses_yaml_summary(source_path))
+    }
+
+    /// Describes `source_path` without reading it: `line_count` is 0 and there is no blob hash.
+    pub fn for_path(project: &Project, source_path: &str) -> Self {
+        Self::describe_path(relative_to_source(project, source_path), project.uses_yaml_summary(source_path))
+    }
+
+    /// Reads the metadata back from a point payload; `None` for points stored before it existed.
+    pub fn from_payload(payload: &HashMap<String, Value>) -> Option<Self> {
+        let string = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        Some(FileMetadata {
+            relative_path: string("relative_path")?,
+            relative_dir: string("relative_dir").unwrap_or_default(),
+            extension: string("extension").unwrap_or_default(),
+            language: string("language").unwrap_or_default(),
+            is_yaml: payload.get("is_yaml").and_then(|v| v.as_bool()).unwrap_or(false),
+            line_count: payload.get("line_count").and_then(|v| v.as_u64()).unwrap_or(0),
+            git_blob_hash: string("git_blob_hash"),
+        })
+    }
+
+    pub fn insert_into(&self, payload: &mut HashMap<String, Value>) {
+        payload.insert("relative_path".to_string(), Value::from(self.relative_path.as_str()));
+        payload.insert("relative_dir".to_string(), Value::from(self.relative_dir.as_str()));
+        payload.insert("extension".to_string(), Value::from(self.extension.as_str()));
+        payload.insert("language".to_string(), Value::from(self.language.as_str()));
+        payload.insert("is_yaml".to_string(), Value::from(self.is_yaml));
+        payload.insert("line_count".to_string(), Value::from(self.line_count));
+        if let Some(hash) = &self.git_blob_hash {
+            payload.insert("git_blob_hash".to_string(), Value::from(hash.as_str()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn holds(self, value: u64, bound: u64) -> bool {
+        match self {
+            Comparison::Less => value < bound,
+            Comparison::LessOrEqual => value <= bound,
+            Comparison::Greater => value > bound,
+            Comparison::GreaterOrEqual => value >= bound,
+            Comparison::Equal => value == bound,
+        }
+    }
+}
+
+/// One filter term. Lists match if any of their values match.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCondition {
+    /// Globs over the relative path (`**` crosses directories, `*` and `?` don't);
+    /// a pattern without wildcards also matches everything below that directory
+    Path(Vec<String>),
+    Extension(Vec<String>),
+    Language(Vec<String>),
+    Yaml(bool),
+    Lines(Comparison, u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterTerm {
+    pub negated: bool,
+    pub condition: FilterCondition,
+}
+
+/// A filter expression over point metadata. Space separated terms that must all hold:
+///
+/// - `path:src/services/**` (or `dir:`), comma separated for alternatives; a bare term is a path glob
+/// - `ext:rs,ts` or a bare `.rs`
+/// - `lang:rust`
+/// - `yaml:true` / `yaml:false`
+/// - `lines:<500`, `lines:>=20` (also `<=`, `>`, or a plain number for equality)
+///
+/// A leading `-` excludes instead, e.g. `-path:**/tests/**`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PayloadFilter {
+    pub terms: Vec<FilterTerm>,
+}
+
+impl TryFrom<String> for PayloadFilter {
+    type Error = String;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        PayloadFilter::parse(&expression)
+    }
+}
+
+fn parse_list(values: &str, lowercase: bool) -> Vec<String> {
+    values
+        .split(',')
+        .map(|value| value.trim().trim_start_matches('.'))
+        .filter(|value| !value.is_empty())
+        .map(|value| if lowercase { value.to_lowercase() } else { value.to_string() })
+        .collect()
+}
+
+fn parse_condition(term: &str) -> Result<FilterCondition, String> {
+    let Some((field, value)) = term.split_once(':') else {
+        if term.starts_with('.') && !term.contains('/') {
+            return Ok(FilterCondition::Extension(parse_list(term, true)));
+        }
+        return Ok(FilterCondition::Path(term.split(',').map(str::to_string).collect()));
+    };
+
+    let condition = match field.to_lowercase().as_str() {
+        "path" | "dir" => FilterCondition::Path(
+            value.split(',').filter(|glob| !glob.is_empty()).map(str::to_string).collect(),
+        ),
+        "ext" => FilterCondition::Extension(parse_list(value, true)),
+        "lang" => FilterCondition::Language(parse_list(value, true)),
+        "yaml" => match value.to_lowercase().as_str() {
+            "true" | "yes" => FilterCondition::Yaml(true),
+            "false" | "no" => FilterCondition::Yaml(false),
+            _ => return Err(format!("yaml filter must be true or false, got '{}'", value)),
+        },
+        "lines" => {
+            let (comparison, bound) = if let Some(bound) = value.strip_prefix("<=") {
+                (Comparison::LessOrEqual, bound)
+            } else if let Some(bound) = value.strip_prefix(">=") {
+                (Comparison::GreaterOrEqual, bound)
+            } else if let Some(bound) = value.strip_prefix('<') {
+                (Comparison::Less, bound)
+            } else if let Some(bound) = value.strip_prefix('>') {
+                (Comparison::Greater, bound)
+            } else {
+                (Comparison::Equal, value)
+            };
+            let bound = bound
+                .trim()
+                .parse()
+                .map_err(|_| format!("lines filter needs a number, got '{}'", value))?;
+            FilterCondition::Lines(comparison, bound)
+        }
+        _ => {
+            return Err(format!(
+                "Unknown filter field '{}' (expected path, dir, ext, lang, yaml or lines)",
+                field
+            ))
+        }
+    };
+
+    match &condition {
+        FilterCondition::Path(values) | FilterCondition::Extension(values) | FilterCondition::Language(values)
+            if values.is_empty() =>
+        {
+            Err(format!("Filter term '{}' has no values", term))
+        }
+        _ => Ok(condition),
+    }
+}
+
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` may also stand for no directory at all
+            if let ['/', after @ ..] = rest {
+                if glob_match(after, path) {
+                    return true;
+                }
+            }
+            (0..=path.len()).any(|i| glob_match(rest, &path[i..]))
+        }
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != '/')
+            .any(|i| glob_match(rest, &path[i..])),
+        ['?', rest @ ..] => matches!(path, [c, tail @ ..] if *c != '/' && glob_match(rest, tail)),
+        [c, rest @ ..] => matches!(path, [p, tail @ ..] if p == c && glob_match(rest, tail)),
+    }
+}
+
+/// Matches `relative_path` against a path glob (see `FilterCondition::Path`).
+pub fn path_matches(pattern: &str, relative_path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+    if !pattern.contains(['*', '?']) {
+        let dir = pattern.trim_end_matches('/');
+        return relative_path == dir || relative_path.starts_with(&format!("{}/", dir));
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = relative_path.chars().collect();
+    glob_match(&pattern, &path)
+}
+
+impl FilterCondition {
+    fn matches(&self, metadata: &FileMetadata) -> bool {
+        match self {
+            FilterCondition::Path(globs) => globs.iter().any(|glob| path_matches(glob, &metadata.relative_path)),
+            FilterCondition::Extension(extensions) => extensions.contains(&metadata.extension),
+            FilterCondition::Language(languages) => languages.contains(&metadata.language),
+            FilterCondition::Yaml(is_yaml) => metadata.is_yaml == *is_yaml,
+            FilterCondition::Lines(comparison, bound) => comparison.holds(metadata.line_count, *bound),
+        }
+    }
+}
+
+impl PayloadFilter {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let terms = expression
+            .split_whitespace()
+            .map(|term| {
+                let (negated, term) = match term.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, term),
+                };
+                Ok(FilterTerm { negated, condition: parse_condition(term)? })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(PayloadFilter { terms })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, metadata: &FileMetadata) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.condition.matches(metadata) != term.negated)
+    }
+
+    /// Points stored without metadata only pass an empty filter.
+    pub fn matches_payload(&self, payload: &HashMap<String, Value>) -> bool {
+        self.is_empty() || FileMetadata::from_payload(payload).is_some_and(|metadata| self.matches(&metadata))
+    }
+
+    /// Whether a term needs the file's content rather than just its path.
+    fn needs_content(&self) -> bool {
+        self.terms.iter().any(|term| matches!(term.condition, FilterCondition::Lines(..)))
+    }
+
+    /// For results that don't come from the vector store (keyword and symbol indexes). The
+    /// file is only read when a `lines:` term needs its line count.
+    pub fn matches_file(&self, project: &Project, source_path: &str) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let mut metadata = FileMetadata::for_path(project, source_path);
+        if self.needs_content() {
+            metadata.line_count = count_lines(&std::fs::read(source_path).unwrap_or_default());
+        }
+        self.matches(&metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(relative_path: &str, lines: usize) -> FileMetadata {
+        FileMetadata::describe(relative_path, "x\n".repeat(lines).as_bytes(), true)
+    }
+
+    #[test]
+    fn describes_files() {
+        let described = FileMetadata::describe("src/services/mod.rs", b"a\nb\nc", false);
+        assert_eq!(described.relative_dir, "src/services");
+        assert_eq!(described.extension, "rs");
+        assert_eq!(described.language, "rust");
+        assert_eq!(described.line_count, 3);
+        assert_eq!(
+            described.git_blob_hash,
+            Some(Oid::hash_object(ObjectType::Blob, b"a\nb\nc").unwrap().to_string())
+        );
+
+        let mut payload = HashMap::new();
+        described.insert_into(&mut payload);
+        assert_eq!(FileMetadata::from_payload(&payload), Some(described));
+    }
+
+    #[test]
+    fn globs_match_paths() {
+        assert!(path_matches("src/services/**", "src/services/vector_store/mod.rs"));
+        assert!(path_matches("src/services", "src/services/mod.rs"));
+        assert!(!path_matches("src/services", "src/services_old/mod.rs"));
+        assert!(path_matches("src/*.rs", "src/main.rs"));
+        assert!(!path_matches("src/*.rs", "src/routes/mod.rs"));
+        assert!(path_matches("**/tests/**", "tests/search.rs"));
+        assert!(path_matches("**/tests/**", "src/tests/search.rs"));
+        assert!(path_matches("src/mai?.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn parses_and_applies_expressions() {
+        let filter = PayloadFilter::parse("path:src/services/** .rs -path:**/tests/** lines:<100").unwrap();
+        assert!(filter.matches(&metadata("src/services/search_service.rs", 10)));
+        assert!(!filter.matches(&metadata("src/services/tests/search.rs", 10)));
+        assert!(!filter.matches(&metadata("src/services/App.svelte", 10)));
+        assert!(!filter.matches(&metadata("src/services/search_service.rs", 100)));
+
+        let filter = PayloadFilter::parse("ext:ts,svelte yaml:true").unwrap();
+        assert!(filter.matches(&metadata("frontend/App.svelte", 1)));
+        assert!(!filter.matches(&metadata("src/main.rs", 1)));
+
+        assert!(PayloadFilter::parse("lines:>10 .rs").unwrap().needs_content());
+        assert!(!PayloadFilter::parse("path:src/** -lang:rust").unwrap().needs_content());
+
+        assert!(PayloadFilter::parse("size:10").is_err());
+        assert!(PayloadFilter::parse("lines:many").is_err());
+        assert!(PayloadFilter::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn payloads_without_metadata_only_pass_empty_filters() {
+        let payload = HashMap::from([("file_path".to_string(), Value::from("/repo/src/main.rs"))]);
+        assert!(PayloadFilter::default().matches_payload(&payload));
+        assert!(!PayloadFilter::parse("lang:rust").unwrap().matches_payload(&payload));
+    }
+}
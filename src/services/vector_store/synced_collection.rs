@@ -0,0 +1,227 @@
+// src/services/vector_store/synced_collection.rs
+use super::{open_project_store, VectorPoint};
+use crate::models::{EmbeddingConfig, Project, VectorBackend};
+use crate::services::embedding_service::EmbeddingService;
+use git2::{ObjectType, Oid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use uuid::Uuid;
+
+/// One lock per state file, so concurrent syncs of the same collection don't embed the same
+/// documents twice while other projects and collections sync independently.
+static SYNC_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+
+fn sync_lock(state_path: &Path) -> Arc<tokio::sync::Mutex<()>> {
+    let locks = SYNC_LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    locks.lock().unwrap().entry(state_path.to_path_buf()).or_default().clone()
+}
+
+/// A document mirrored into a collection, e.g. a chat message or a note. `key` is stable
+/// across edits; the point is re-embedded whenever its text or payload changes.
+pub struct SyncDocument {
+    pub key: String,
+    pub text: String,
+    pub payload: HashMap<String, Value>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub embedded: usize,
+    pub removed: usize,
+}
+
+/// What is in a synced collection: the embedding config and vector backend it was built
+/// with and a hash of every indexed document, keyed by document key.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    embedding: Option<EmbeddingConfig>,
+    #[serde(default)]
+    backend: Option<VectorBackend>,
+    documents: HashMap<String, String>,
+}
+
+impl SyncState {
+    /// Whether the collection has to be rebuilt: it was embedded with another config, or
+    /// lives in another backend than the project now uses (whose collection starts empty).
+    fn is_stale(&self, config: &EmbeddingConfig, backend: VectorBackend) -> bool {
+        self.embedding.as_ref() != Some(config) || self.backend != Some(backend)
+    }
+}
+
+fn hash_text(text: &str) -> String {
+    Oid::hash_object(ObjectType::Blob, text.as_bytes())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default()
+}
+
+impl SyncDocument {
+    fn hash(&self) -> String {
+        let mut payload: Vec<(&String, &Value)> = self.payload.iter().collect();
+        payload.sort_by_key(|(key, _)| key.as_str());
+        hash_text(&format!("{}\n{:?}", self.text, payload))
+    }
+}
+
+/// Point id for a document key; Qdrant only accepts UUIDs and integers.
+pub fn point_id_for_key(key: &str) -> String {
+    let hash = Oid::hash_object(ObjectType::Blob, key.as_bytes()).unwrap_or_else(|_| Oid::zero());
+    Uuid::from_slice(&hash.as_bytes()[..16]).unwrap_or_default().to_string()
+}
+
+/// Document keys to (re-)embed and keys to drop, given what is indexed and what exists now.
+fn plan_sync(indexed: &HashMap<String, String>, current: &HashMap<String, String>) -> (Vec<String>, Vec<String>) {
+    let mut to_embed: Vec<String> = current
+        .iter()
+        .filter(|(key, hash)| indexed.get(*key) != Some(*hash))
+        .map(|(key, _)| key.clone())
+        .collect();
+    let mut to_remove: Vec<String> = indexed.keys().filter(|key| !current.contains_key(*key)).cloned().collect();
+    to_embed.sort();
+    to_remove.sort();
+    (to_embed, to_remove)
+}
+
+/// Brings `collection_name` up to date with `documents`: new and changed documents are
+/// embedded, missing ones removed. What was indexed is tracked in `<project_dir>/<state_file>`;
+/// a changed embedding config or vector backend rebuilds the collection from scratch.
+pub async fn sync_collection(
+    project: &Project,
+    project_dir: &Path,
+    collection_name: &str,
+    state_file: &str,
+    documents: Vec<SyncDocument>,
+) -> Result<SyncReport, String> {
+    let state_path = project_dir.join(state_file);
+    let lock = sync_lock(&state_path);
+    let _guard = lock.lock().await;
+
+    let mut state: SyncState = std::fs::read_to_string(&state_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let vector_store = open_project_store(project, project_dir)
+        .await
+        .map_err(|e| format!("Failed to open vector store: {}", e))?;
+    if state.is_stale(&project.embedding_config, project.vector_backend) {
+        vector_store
+            .delete_collection(collection_name)
+            .await
+            .map_err(|e| format!("Failed to reset {}: {}", collection_name, e))?;
+        state = SyncState {
+            embedding: Some(project.embedding_config.clone()),
+            backend: Some(project.vector_backend),
+            documents: HashMap::new(),
+        };
+    }
+    vector_store
+        .create_collection(collection_name, project.embedding_config.dimensions as u64)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", collection_name, e))?;
+
+    let current: HashMap<String, String> =
+        documents.iter().map(|document| (document.key.clone(), document.hash())).collect();
+    let (to_embed, to_remove) = plan_sync(&state.documents, &current);
+    if to_embed.is_empty() && to_remove.is_empty() {
+        return Ok(SyncReport::default());
+    }
+
+    if !to_remove.is_empty() {
+        let point_ids = to_remove.iter().map(|key| point_id_for_key(key)).collect();
+        vector_store
+            .delete_points(collection_name, point_ids)
+            .await
+            .map_err(|e| format!("Failed to remove points from {}: {}", collection_name, e))?;
+        for key in &to_remove {
+            state.documents.remove(key);
+        }
+    }
+
+    let to_embed: HashSet<String> = to_embed.into_iter().collect();
+    let documents: Vec<SyncDocument> = documents.into_iter().filter(|document| to_embed.contains(&document.key)).collect();
+    let texts: Vec<String> = documents.iter().map(|document| document.text.clone()).collect();
+    let embeddings = EmbeddingService::for_project(project_dir)
+        .generate_embeddings(&texts, &project.embedding_config)
+        .await
+        .map_err(|e| format!("Failed to embed documents for {}: {}", collection_name, e))?;
+
+    let mut points = Vec::new();
+    for (document, embedding) in documents.into_iter().zip(embeddings) {
+        state.documents.insert(document.key.clone(), current[&document.key].clone());
+        points.push(VectorPoint {
+            id: point_id_for_key(&document.key),
+            vector: embedding,
+            payload: document.payload,
+        });
+    }
+    let embedded = points.len();
+    vector_store
+        .upsert_points(collection_name, points)
+        .await
+        .map_err(|e| format!("Failed to store embeddings in {}: {}", collection_name, e))?;
+
+    let json = serde_json::to_string(&state).map_err(|e| e.to_string())?;
+    let tmp_path = state_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json)
+        .and_then(|_| std::fs::rename(&tmp_path, &state_path))
+        .map_err(|e| format!("Failed to save sync state for {}: {}", collection_name, e))?;
+
+    println!("Synced {}: {} embedded, {} removed", collection_name, embedded, to_remove.len());
+    Ok(SyncReport { embedded, removed: to_remove.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_new_changed_and_removed_documents() {
+        let indexed = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "3".to_string()),
+        ]);
+        let current = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "changed".to_string()),
+            ("d".to_string(), "4".to_string()),
+        ]);
+        let (to_embed, to_remove) = plan_sync(&indexed, &current);
+        assert_eq!(to_embed, vec!["b", "d"]);
+        assert_eq!(to_remove, vec!["c"]);
+    }
+
+    #[test]
+    fn resets_on_config_or_backend_change() {
+        let config = EmbeddingConfig::default();
+        let state = SyncState {
+            embedding: Some(config.clone()),
+            backend: Some(VectorBackend::Qdrant),
+            documents: HashMap::new(),
+        };
+        assert!(!state.is_stale(&config, VectorBackend::Qdrant));
+        assert!(state.is_stale(&config, VectorBackend::Local));
+        let other = EmbeddingConfig { dimensions: config.dimensions + 1, ..config.clone() };
+        assert!(state.is_stale(&other, VectorBackend::Qdrant));
+
+        // State files written before the backend was recorded are rebuilt once
+        let legacy: SyncState = serde_json::from_str(&format!(
+            r#"{{"embedding": {}, "documents": {{"a": "1"}}}}"#,
+            serde_json::to_string(&config).unwrap()
+        ))
+        .unwrap();
+        assert!(legacy.is_stale(&config, VectorBackend::Qdrant));
+        assert!(SyncState::default().is_stale(&config, VectorBackend::Local));
+    }
+
+    #[test]
+    fn point_ids_are_stable_uuids() {
+        let id = point_id_for_key("notes/conventions");
+        assert_eq!(id, point_id_for_key("notes/conventions"));
+        assert_ne!(id, point_id_for_key("notes/other"));
+        assert!(Uuid::parse_str(&id).is_ok());
+    }
+}
@@ -0,0 +1,337 @@
+// src/services/yaml/keyword_index.rs
+use crate::services::tokenizer::tokenize;
+use crate::services::yaml::{FileYamlData, Function};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const INDEX_FILE: &str = "keyword_index.json";
+/// Bumped whenever fields or tokenization change; an index with another version is rebuilt.
+const INDEX_VERSION: u32 = 2;
+
+/// BM25 term-frequency saturation.
+const K1: f32 = 1.2;
+
+/// Fields of a YAML summary that are indexed separately, each with its own weight and
+/// length normalization.
+#[derive(Clone, Copy)]
+enum Field {
+    Path,
+    Description,
+    FunctionName,
+    FunctionDescription,
+    Parameter,
+    Call,
+    DataStructure,
+    Import,
+}
+
+const FIELD_COUNT: usize = 8;
+/// (weight, b) per field, indexed by `Field as usize`.
+const FIELD_PARAMS: [(f32, f32); FIELD_COUNT] = [
+    (0.5, 0.3),  // Path
+    (2.0, 0.75), // Description
+    (3.0, 0.5),  // FunctionName
+    (1.0, 0.75), // FunctionDescription
+    (1.0, 0.5),  // Parameter
+    (1.5, 0.5),  // Call
+    (2.5, 0.5),  // DataStructure
+    (1.0, 0.3),  // Import
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct IndexedDoc {
+    /// Term -> frequency in each field
+    term_freqs: HashMap<String, [u32; FIELD_COUNT]>,
+    field_lengths: [u32; FIELD_COUNT],
+}
+
+/// Field-weighted BM25F inverted index over a project's YAML summaries, keyed by the same
+/// source paths as `Project.embeddings`. Persisted as `output/<project>/keyword_index.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct KeywordIndex {
+    version: u32,
+    docs: HashMap<String, IndexedDoc>,
+    /// Term -> source paths containing it in any field
+    postings: HashMap<String, HashSet<String>>,
+    field_length_totals: [u64; FIELD_COUNT],
+}
+
+/// Loaded indexes, keyed by project output dir.
+static INDEXES: OnceLock<Mutex<HashMap<PathBuf, KeywordIndex>>> = OnceLock::new();
+
+/// Source path a YAML file in the project output dir was generated for.
+pub fn source_path_for_yaml(yaml_file_name: &str) -> Option<String> {
+    yaml_file_name
+        .strip_suffix(".yml")
+        .filter(|name| !name.is_empty())
+        .map(|name| name.replace("*", "/"))
+}
+
+impl KeywordIndex {
+    fn new() -> Self {
+        KeywordIndex {
+            version: INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// Builds the index from every YAML file in `project_dir`.
+    fn build(project_dir: &Path) -> Self {
+        let mut index = KeywordIndex::new();
+        if let Ok(entries) = std::fs::read_dir(project_dir) {
+            for entry in entries.filter_map(Result::ok) {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let Some(source_path) = source_path_for_yaml(&file_name) else {
+                    continue;
+                };
+                match std::fs::read_to_string(entry.path()) {
+                    Ok(content) => index.insert_yaml(&source_path, &content),
+                    Err(e) => eprintln!("Failed to read {} for keyword index: {}", file_name, e),
+                }
+            }
+        }
+        index
+    }
+
+    fn insert_yaml(&mut self, source_path: &str, yaml_content: &str) {
+        match serde_yaml::from_str::<FileYamlData>(yaml_content) {
+            Ok(yaml_data) => self.insert(source_path, &yaml_data),
+            Err(e) => {
+                eprintln!("Not indexing {} for keyword search, YAML did not parse: {}", source_path, e);
+                self.remove(source_path);
+            }
+        }
+    }
+
+    fn insert(&mut self, source_path: &str, yaml_data: &FileYamlData) {
+        self.remove(source_path);
+
+        let mut doc = IndexedDoc::default();
+        let mut add = |field: Field, text: &str| {
+            for term in tokenize(text) {
+                doc.term_freqs.entry(term).or_insert([0; FIELD_COUNT])[field as usize] += 1;
+                doc.field_lengths[field as usize] += 1;
+            }
+        };
+
+        add(Field::Path, source_path);
+        add(Field::Description, &yaml_data.description);
+        let methods = yaml_data.classes.iter().flat_map(|c| c.methods.iter());
+        for function in yaml_data.functions.iter().chain(methods) {
+            add_function(&mut add, function);
+        }
+        for class in &yaml_data.classes {
+            add(Field::DataStructure, &class.name);
+            for property in &class.properties {
+                add(Field::Parameter, &property.name);
+            }
+        }
+        for data_structure in &yaml_data.data_structures {
+            add(Field::DataStructure, &data_structure.name);
+        }
+        for import in &yaml_data.imports {
+            add(Field::Import, import);
+        }
+
+        for term in doc.term_freqs.keys() {
+            self.postings.entry(term.clone()).or_default().insert(source_path.to_string());
+        }
+        for (total, length) in self.field_length_totals.iter_mut().zip(doc.field_lengths) {
+            *total += length as u64;
+        }
+        self.docs.insert(source_path.to_string(), doc);
+    }
+
+    fn remove(&mut self, source_path: &str) {
+        let Some(doc) = self.docs.remove(source_path) else {
+            return;
+        };
+        for term in doc.term_freqs.keys() {
+            if let Some(paths) = self.postings.get_mut(term) {
+                paths.remove(source_path);
+                if paths.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        for (total, length) in self.field_length_totals.iter_mut().zip(doc.field_lengths) {
+            *total = total.saturating_sub(length as u64);
+        }
+    }
+
+    /// Ranks documents for `query` with BM25F: per-field frequencies are length-normalized,
+    /// weighted and summed before a single saturation, so a term repeated across fields
+    /// can't outscore a term matched once in a strong field by much.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<(String, f32)> {
+        let doc_count = self.docs.len() as f32;
+        if doc_count == 0.0 {
+            return Vec::new();
+        }
+        let avg_lengths: Vec<f32> = self
+            .field_length_totals
+            .iter()
+            .map(|&total| (total as f32 / doc_count).max(1.0))
+            .collect();
+
+        let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+
+        for term in &query_terms {
+            let Some(paths) = self.postings.get(term) else {
+                continue;
+            };
+            let doc_freq = paths.len() as f32;
+            let idf = (1.0 + (doc_count - doc_freq + 0.5) / (doc_freq + 0.5)).ln();
+
+            for path in paths {
+                let doc = &self.docs[path];
+                let freqs = doc.term_freqs[term];
+                let weighted_tf: f32 = (0..FIELD_COUNT)
+                    .filter(|&field| freqs[field] > 0)
+                    .map(|field| {
+                        let (weight, b) = FIELD_PARAMS[field];
+                        let norm = 1.0 - b + b * doc.field_lengths[field] as f32 / avg_lengths[field];
+                        weight * freqs[field] as f32 / norm
+                    })
+                    .sum();
+                *scores.entry(path.as_str()).or_insert(0.0) += idf * weighted_tf / (K1 + weighted_tf);
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(path, score)| (path.to_string(), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+fn add_function(add: &mut impl FnMut(Field, &str), function: &Function) {
+    add(Field::FunctionName, &function.name);
+    if let Some(description) = &function.description {
+        add(Field::FunctionDescription, description);
+    }
+    for parameter in &function.parameters {
+        add(Field::Parameter, &parameter.name);
+        add(Field::Parameter, &parameter.param_type);
+    }
+    for call in &function.calls {
+        add(Field::Call, call);
+    }
+}
+
+fn load_or_build(project_dir: &Path) -> KeywordIndex {
+    let stored = std::fs::read_to_string(project_dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<KeywordIndex>(&json).ok())
+        .filter(|index| index.version == INDEX_VERSION);
+    match stored {
+        Some(index) => index,
+        None => {
+            println!("Building keyword index for {}", project_dir.display());
+            let index = KeywordIndex::build(project_dir);
+            save(project_dir, &index);
+            index
+        }
+    }
+}
+
+fn save(project_dir: &Path, index: &KeywordIndex) {
+    let result = serde_json::to_string(index)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            let tmp_path = project_dir.join(format!("{}.tmp", INDEX_FILE));
+            std::fs::write(&tmp_path, json)
+                .and_then(|_| std::fs::rename(&tmp_path, project_dir.join(INDEX_FILE)))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to save keyword index: {}", e);
+    }
+}
+
+/// Runs `f` on the project's index, loading (or building) it first if needed.
+fn with_index<T>(project_dir: &Path, f: impl FnOnce(&mut KeywordIndex) -> T) -> T {
+    let mut indexes = INDEXES.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    let index = indexes
+        .entry(project_dir.to_path_buf())
+        .or_insert_with(|| load_or_build(project_dir));
+    f(index)
+}
+
+/// Re-indexes one file after its YAML was (re)generated.
+pub fn update_file(project_dir: &Path, source_path: &str, yaml_content: &str) {
+    with_index(project_dir, |index| {
+        index.insert_yaml(source_path, yaml_content);
+        save(project_dir, index);
+    });
+}
+
+pub fn remove_file(project_dir: &Path, source_path: &str) {
+    with_index(project_dir, |index| {
+        index.remove(source_path);
+        save(project_dir, index);
+    });
+}
+
+pub fn search(project_dir: &Path, query: &str, limit: usize) -> Vec<(String, f32)> {
+    with_index(project_dir, |index| index.search(query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(description: &str, function: &str, calls: &[&str]) -> FileYamlData {
+        serde_yaml::from_str(&format!(
+            "description: {}\nfunctions:\n  - name: {}\n    calls: [{}]\n",
+            description,
+            function,
+            calls.join(", ")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn ranks_function_name_match_above_description_mention() {
+        let mut index = KeywordIndex::new();
+        index.insert("src/a.rs", &yaml("Parses config files", "load_settings", &[]));
+        index.insert("src/b.rs", &yaml("Talks about settings once", "run", &[]));
+        index.insert("src/c.rs", &yaml("Unrelated", "noop", &[]));
+
+        let results = index.search("settings", 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "src/a.rs");
+    }
+
+    #[test]
+    fn reinserting_replaces_previous_terms() {
+        let mut index = KeywordIndex::new();
+        index.insert("src/a.rs", &yaml("Old summary", "old_name", &[]));
+        index.insert("src/a.rs", &yaml("New summary", "new_name", &["helper"]));
+
+        assert!(index.search("old", 10).is_empty());
+        assert_eq!(index.search("helper", 10)[0].0, "src/a.rs");
+        assert_eq!(index.docs.len(), 1);
+    }
+
+    #[test]
+    fn remove_clears_postings_and_lengths() {
+        let mut index = KeywordIndex::new();
+        index.insert("src/a.rs", &yaml("Summary", "name", &[]));
+        index.remove("src/a.rs");
+
+        assert!(index.postings.is_empty());
+        assert!(index.field_length_totals.iter().all(|&total| total == 0));
+    }
+
+    #[test]
+    fn maps_yaml_file_names_to_source_paths() {
+        assert_eq!(source_path_for_yaml("src*main.rs.yml"), Some("src/main.rs".to_string()));
+        assert_eq!(source_path_for_yaml("project_settings.json"), None);
+    }
+}
@@ -1,26 +1,31 @@
 // src/services/yaml/management/cleanup.rs
-use crate::services::qdrant_service::QdrantService;
-use std::env;
+use crate::models::Project;
+use crate::services::yaml::management::incremental;
+use crate::services::vector_store::{self, project_collection_name};
+use std::path::Path;
 
-pub fn clean_up_orphaned_files(project_name: &str, orphaned_files: Vec<String>) {
-    let qdrant_server_url = env::var("QDRANT_SERVER_URL")
-        .unwrap_or_else(|_| "http://localhost:6334".to_string());
-        
-    // Clone project_name to own the data for the async task
-    let project_name_owned = project_name.to_string();
+pub fn clean_up_orphaned_files(project: &Project, project_dir: &Path, orphaned_files: Vec<String>) {
+    for file_path in &orphaned_files {
+        incremental::remove_source_snapshot(project_dir, file_path);
+    }
+
+    // Clone the project and its directory to own the data for the async task
+    let project_owned = project.clone();
+    let project_dir_owned = project_dir.to_path_buf();
 
     // Spawn a task to clean up vectors
     tokio::spawn(async move {
-        match QdrantService::new(&qdrant_server_url, 1536).await {
-            Ok(qdrant_service) => {
+        match vector_store::open_project_store(&project_owned, &project_dir_owned).await {
+            Ok(vector_store) => {
+                let collection_name = project_collection_name(&project_owned);
                 for file_path in orphaned_files {
-                    match qdrant_service.delete_file_vectors(&project_name_owned, &file_path).await {
+                    match vector_store.delete_file_vectors(&collection_name, &file_path).await {
                         Ok(_) => println!("Removed vector for orphaned file: {}", file_path),
                         Err(e) => eprintln!("Failed to remove vector for {}: {}", file_path, e),
                     }
                 }
             },
-            Err(e) => eprintln!("Failed to connect to Qdrant for cleanup: {}", e),
+            Err(e) => eprintln!("Failed to open vector store for cleanup: {}", e),
         }
     });
-}
\ No newline at end of file
+}
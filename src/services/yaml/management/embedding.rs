@@ -1,32 +1,35 @@
 // src/services/yaml/management/embedding.rs
-use crate::models::EmbeddingMetadata;
+use crate::models::{EmbeddingConfig, EmbeddingMetadata};
 use crate::services::embedding_service::EmbeddingService;
-use crate::services::qdrant_service::QdrantService;
+use crate::services::vector_store::{self, project_collection_name, FileMetadata, VectorPoint, VectorStore, VectorStoreResult, SYMBOL_POINT};
+use crate::services::yaml::management::symbol_chunks;
 use crate::models::Project;
+use std::collections::HashMap;
 use std::path::Path;
-use std::env;
 use std::fs::write;
 use crate::services::git_service::GitService; // Import GitService
 use chrono::{DateTime, Utc}; // Import for timestamp comparison
 
 pub async fn process_embedding(
     embedding_service: &EmbeddingService,
-    qdrant_service: &QdrantService,
+    vector_store: &dyn VectorStore,
     project: &mut Project,
     source_path: &str,
     content_to_embed: &String, // Renamed from yaml_content for clarity, as it can be source or yaml
     git_blob_hash: Option<String>, // Add this parameter
 ) {
-    match embedding_service.generate_embedding(content_to_embed, Some(1536)).await {
-        Ok(embedding) => {
-            // Store embedding
-            let vector_id = qdrant_service.store_file_embedding(
-                &project.name,
-                source_path,
-                content_to_embed,
-                embedding
-            ).await.unwrap();
-
+    let collection_name = project_collection_name(project);
+    let file_metadata = FileMetadata::for_file(project, source_path);
+    match embed_into_collection(
+        embedding_service,
+        vector_store,
+        &collection_name,
+        &project.embedding_config,
+        source_path,
+        content_to_embed,
+        &file_metadata,
+    ).await {
+        Ok(vector_id) => {
             // Update project embeddings metadata
             let metadata = EmbeddingMetadata {
                 file_path: source_path.to_string(),
@@ -36,10 +39,171 @@ pub async fn process_embedding(
             };
             project.embeddings.insert(source_path.to_string(), metadata);
         },
-        Err(e) => eprintln!("Failed to generate embedding: {}", e),
+        Err(e) => eprintln!("Failed to embed {}: {}", source_path, e),
+    }
+}
+
+/// Embeds a file with the given config and replaces its points in `collection_name`.
+/// YAML summaries additionally get one point per function, method and data structure.
+/// Every point carries `file_metadata` for filtered search. Returns the id of the file-level point.
+pub async fn embed_into_collection(
+    embedding_service: &EmbeddingService,
+    vector_store: &dyn VectorStore,
+    collection_name: &str,
+    config: &EmbeddingConfig,
+    source_path: &str,
+    content_to_embed: &str,
+    file_metadata: &FileMetadata,
+) -> VectorStoreResult<String> {
+    let embedding = embedding_service.generate_embedding(content_to_embed, config).await?;
+
+    // Store embedding (this also clears the file's old symbol points)
+    let vector_id = vector_store.store_file_embedding(
+        collection_name,
+        source_path,
+        content_to_embed,
+        embedding,
+        file_metadata,
+    ).await?;
+
+    if let Some(yaml_data) = symbol_chunks::parse_yaml_content(content_to_embed) {
+        store_symbol_embeddings(embedding_service, vector_store, collection_name, config, source_path, &vector_id, &yaml_data, file_metadata).await;
+    }
+
+    Ok(vector_id)
+}
+
+/// Reads what should be embedded for `file_path`: its YAML summary when `Project::uses_yaml_summary`,
+/// otherwise the original source.
+pub fn read_content_to_embed(project: &Project, file_path: &str, project_dir: &Path) -> Result<String, String> {
+    if project.uses_yaml_summary(file_path) {
+        let yaml_path = project_dir.join(format!("{}.yml", file_path.replace("/", "*")));
+        std::fs::read_to_string(&yaml_path).map_err(|e| format!("Error reading YAML file: {}", e))
+    } else {
+        std::fs::read_to_string(file_path).map_err(|e| format!("Error reading original source file: {}", e))
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+async fn store_symbol_embeddings(
+    embedding_service: &EmbeddingService,
+    vector_store: &dyn VectorStore,
+    collection_name: &str,
+    config: &EmbeddingConfig,
+    source_path: &str,
+    file_point_id: &str,
+    yaml_data: &crate::services::yaml::FileYamlData,
+    file_metadata: &FileMetadata,
+) {
+    let chunks = symbol_chunks::build_symbol_chunks(source_path, yaml_data);
+    let mut points = Vec::new();
+
+    for chunk in chunks {
+        match embedding_service.generate_embedding(&chunk.text, config).await {
+            Ok(embedding) => points.push(symbol_point(source_path, file_point_id, chunk, embedding, file_metadata)),
+            Err(e) => eprintln!("Failed to generate embedding for symbol {} in {}: {}", chunk.name, source_path, e),
+        }
+    }
+
+    let num_points = points.len();
+    match vector_store.upsert_points(collection_name, points).await {
+        Ok(_) => println!("Stored {} symbol embeddings for file: {}", num_points, source_path),
+        Err(e) => eprintln!("Failed to store symbol embeddings for {}: {}", source_path, e),
+    }
+}
+
+fn symbol_point(
+    source_path: &str,
+    file_point_id: &str,
+    chunk: symbol_chunks::SymbolChunk,
+    embedding: Vec<f32>,
+    file_metadata: &FileMetadata,
+) -> VectorPoint {
+    let mut payload = HashMap::new();
+    payload.insert("file_path".to_string(), serde_json::Value::from(source_path));
+    payload.insert("file_content".to_string(), serde_json::Value::from(chunk.text));
+    payload.insert("point_type".to_string(), serde_json::Value::from(SYMBOL_POINT));
+    payload.insert("file_point_id".to_string(), serde_json::Value::from(file_point_id));
+    payload.insert("symbol_name".to_string(), serde_json::Value::from(chunk.name));
+    payload.insert("symbol_kind".to_string(), serde_json::Value::from(chunk.kind));
+    if let Some(parent_symbol) = chunk.parent_symbol {
+        payload.insert("parent_symbol".to_string(), serde_json::Value::from(parent_symbol));
+    }
+    if let (Some(start_line), Some(end_line)) = (chunk.start_line, chunk.end_line) {
+        payload.insert("start_line".to_string(), serde_json::Value::from(start_line));
+        payload.insert("end_line".to_string(), serde_json::Value::from(end_line));
+    }
+    file_metadata.insert_into(&mut payload);
+    VectorPoint {
+        id: uuid::Uuid::new_v4().to_string(),
+        vector: embedding,
+        payload,
+    }
+}
+
+/// Embeds several `(source_path, content)` files of `project` with as few embedding requests
+/// and upserts as possible. Returns each file's new file-level point id, or why it failed.
+pub async fn embed_files_batch(
+    embedding_service: &EmbeddingService,
+    vector_store: &dyn VectorStore,
+    collection_name: &str,
+    project: &Project,
+    files: &[(String, String)],
+) -> Vec<(String, Result<String, String>)> {
+    let config = &project.embedding_config;
+    // File texts are followed by their symbol chunk texts, in file order
+    let mut texts = Vec::new();
+    let mut chunks_per_file = Vec::new();
+    for (source_path, content) in files {
+        let chunks = symbol_chunks::parse_yaml_content(content)
+            .map(|yaml_data| symbol_chunks::build_symbol_chunks(source_path, &yaml_data))
+            .unwrap_or_default();
+        texts.push(content.clone());
+        texts.extend(chunks.iter().map(|chunk| chunk.text.clone()));
+        chunks_per_file.push(chunks);
+    }
+
+    let embeddings = match embedding_service.generate_embeddings(&texts, config).await {
+        Ok(embeddings) => embeddings,
+        Err(e) => {
+            let error = format!("Failed to generate embeddings: {}", e);
+            return files.iter().map(|(path, _)| (path.clone(), Err(error.clone()))).collect();
+        }
+    };
+
+    let mut embeddings = embeddings.into_iter();
+    let mut points = Vec::new();
+    // Each file with the ids of its new points
+    let mut stored: Vec<(String, Vec<String>)> = Vec::new();
+    for ((source_path, content), chunks) in files.iter().zip(chunks_per_file) {
+        let file_metadata = FileMetadata::for_file(project, source_path);
+        let file_point = vector_store::file_point(source_path, content, embeddings.next().unwrap_or_default(), &file_metadata);
+        let file_point_id = file_point.id.clone();
+        let mut file_points = vec![file_point];
+        for chunk in chunks {
+            file_points.push(symbol_point(source_path, &file_point_id, chunk, embeddings.next().unwrap_or_default(), &file_metadata));
+        }
+        stored.push((source_path.clone(), file_points.iter().map(|point| point.id.clone()).collect()));
+        points.extend(file_points);
+    }
+
+    // New points land before the old ones go, so a failed upsert leaves the files searchable
+    if let Err(e) = vector_store.upsert_points(collection_name, points).await {
+        let error = format!("Failed to store embeddings: {}", e);
+        return files.iter().map(|(path, _)| (path.clone(), Err(error.clone()))).collect();
+    }
+
+    let mut results = Vec::new();
+    for (source_path, new_ids) in stored {
+        // A leftover old point is only a duplicate; reconciling deletes it as an orphan
+        if let Err(e) = vector_store.delete_stale_file_vectors(collection_name, &source_path, &new_ids).await {
+            eprintln!("Failed to delete old vectors for {}: {}", source_path, e);
+        }
+        results.push((source_path, Ok(new_ids[0].clone())));
+    }
+
+    results
+}
 
 pub async fn check_and_update_yaml_embeddings(project: &mut Project, output_dir: &str)  {
     let output_path = Path::new(output_dir).join(&project.name);
@@ -50,19 +214,18 @@ pub async fn check_and_update_yaml_embeddings(project: &mut Project, output_dir:
         return;
     }
     
-    let embedding_service = EmbeddingService::new();
-    let qdrant_server_url = env::var("QDRANT_SERVER_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
-    
-    let qdrant_service = match QdrantService::new(&qdrant_server_url, 1536).await {
-        Ok(service) => service,
+    let embedding_service = EmbeddingService::for_project(&output_path);
+
+    let vector_store = match vector_store::open_project_store(project, &output_path).await {
+        Ok(store) => store,
         Err(e) => {
-            eprintln!("Failed to connect to Qdrant: {}", e);
+            eprintln!("Failed to open vector store: {}", e);
             return;
         }
     };
     
     // Create collection for this project if it doesn't exist
-    if let Err(e) = qdrant_service.create_project_collection(&project.name).await {
+    if let Err(e) = vector_store.create_collection(&project_collection_name(project), project.embedding_config.dimensions as u64).await {
         eprintln!("Failed to create collection: {}", e);
         return;
     }
@@ -193,7 +356,7 @@ pub async fn check_and_update_yaml_embeddings(project: &mut Project, output_dir:
                     }
 
                     // Generate and Store embedding, passing the current_blob_hash
-                    process_embedding(&embedding_service, &qdrant_service, project, &source_file_path_str, &content_to_embed, current_blob_hash).await;
+                    process_embedding(&embedding_service, vector_store.as_ref(), project, &source_file_path_str, &content_to_embed, current_blob_hash).await;
                     any_updates = true;
                 }
             }
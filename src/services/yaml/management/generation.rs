@@ -1,94 +1,245 @@
 // src/services/yaml/management/generation.rs
+use crate::services::yaml::update_search_indexes;
 use crate::services::yaml::management::{YamlManagement, embedding};
-use crate::models::Project;
+use crate::models::{EmbeddingMetadata, Project, ProjectFile};
 use crate::services::embedding_service::EmbeddingService;
-use crate::services::qdrant_service::QdrantService;
+use crate::services::job_service::{FileProgress, FileStage, JobService};
+use crate::services::project_service::ProjectService;
+use crate::services::vector_store::{self, project_collection_name, VectorStore};
+use std::collections::HashMap;
+use std::env;
 use std::path::Path;
 use std::fs::write;
-use std::env;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use crate::services::git_service::GitService;
 use crate::services::llm_service::LlmServiceConfig; // Import LlmServiceConfig
 
+/// Concurrent LLM YAML conversions, overridable with `INDEXING_CONCURRENCY`.
+const DEFAULT_LLM_CONCURRENCY: usize = 4;
+/// Number of files embedded and upserted together.
+const EMBED_BATCH_FILES: usize = 16;
 
-pub async fn generate_yaml_files(yaml_management: &YamlManagement, project: &mut Project, output_dir: &str, force: bool) {
+pub async fn generate_yaml_files(yaml_management: &YamlManagement, project: &mut Project, output_dir: &str, force: bool, job_id: &str) -> Result<(), String> {
     let output_path = Path::new(output_dir).join(&project.name);
-    std::fs::create_dir_all(&output_path).unwrap();
+    std::fs::create_dir_all(&output_path).map_err(|e| format!("Failed to create output directory: {}", e))?;
 
-    let embedding_service = EmbeddingService::new();
-    let qdrant_server_url = env::var("QDRANT_SERVER_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
-    let qdrant_service = QdrantService::new(&qdrant_server_url, 1536).await.unwrap();
+    let job_service = JobService::new();
+    let embedding_service = EmbeddingService::for_project(&output_path);
+    let vector_store = vector_store::open_project_store(project, &output_path)
+        .await
+        .map_err(|e| format!("Failed to open vector store: {}", e))?;
 
     // Create collection for this project
-    qdrant_service.create_project_collection(&project.name).await.unwrap();
+    let collection_name = project_collection_name(project);
+    vector_store
+        .create_collection(&collection_name, project.embedding_config.dimensions as u64)
+        .await
+        .map_err(|e| format!("Failed to create collection: {}", e))?;
 
     let files = yaml_management.file_service.read_project_files(&project);
 
-    // Open the repo once if git integration is enabled
-    let repo_result = if project.git_integration_enabled {
-        GitService::open_repository(Path::new(&project.source_dir))
-    } else {
-        Err(crate::services::git_service::GitError::Other("Git integration not enabled".to_string()))
-    };
+    // Decide what every file needs before any await; the git repo can't be held across one
+    let mut yaml_jobs: Vec<ProjectFile> = Vec::new();
+    let mut markdown_files: Vec<(String, String)> = Vec::new();
+    let mut blob_hashes: HashMap<String, Option<String>> = HashMap::new();
+    let mut progress: Vec<FileProgress> = Vec::new();
+    {
+        // Open the repo once if git integration is enabled
+        let repo_result = if project.git_integration_enabled {
+            GitService::open_repository(Path::new(&project.source_dir))
+        } else {
+            Err(crate::services::git_service::GitError::Other("Git integration not enabled".to_string()))
+        };
 
-    for file in files {
-        let source_path_buf = Path::new(&file.path);
-        let yaml_path = output_path.join(format!("{}.yml", file.path.replace("/", "*")));
-        let use_yaml = project.file_yaml_override.get(&file.path).map(|&b| b).unwrap_or(project.default_use_yaml);
+        for file in files {
+            let source_path_buf = Path::new(&file.path);
+            let yaml_path = output_path.join(format!("{}.yml", file.path.replace("/", "*")));
+            let use_yaml = project.file_yaml_override.get(&file.path).copied().unwrap_or(project.default_use_yaml);
 
-        let file_extension = source_path_buf
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
+            let file_extension = source_path_buf
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("");
 
-        // Determine if an update is needed using the new `needs_yaml_update` signature
-        let needs_update = if force {
-            true // Force update overrides all checks
-        } else {
-            if file_extension != "md" {
+            // Determine if an update is needed using the new `needs_yaml_update` signature
+            let needs_update = if force {
+                true // Force update overrides all checks
+            } else if file_extension != "md" {
                 yaml_management.file_service.needs_yaml_update(project, &repo_result, source_path_buf, &yaml_path)
             } else {
                 false // Markdown files are always processed, no YAML update needed
+            };
+
+            // Get blob hash if git is enabled and repo is open for the current file
+            let git_blob_hash_for_file = match &repo_result {
+                Ok(repo) => GitService::get_blob_hash(repo, source_path_buf).ok(),
+                Err(_) => None,
+            };
+
+            let stage = if file_extension == "md" {
+                // Handle Markdown files: embed their content directly
+                markdown_files.push((file.path.clone(), file.content.clone()));
+                FileStage::Pending
+            } else if use_yaml && needs_update {
+                FileStage::Pending
+            } else {
+                FileStage::Skipped
+            };
+
+            progress.push(FileProgress { path: file.path.clone(), stage, error: None });
+            blob_hashes.insert(file.path.clone(), git_blob_hash_for_file);
+            if stage == FileStage::Pending && file_extension != "md" {
+                yaml_jobs.push(file);
             }
-        };
-        
-        // Get blob hash if git is enabled and repo is open for the current file
-        let git_blob_hash_for_file = if repo_result.is_ok() {
-            GitService::get_blob_hash(repo_result.as_ref().unwrap(), source_path_buf).ok()
-        } else {
-            None
-        };
+        }
+    }
+    job_service.set_files(job_id, progress);
+
+    let mut batch = EmbedBatch {
+        embedding_service: &embedding_service,
+        vector_store: vector_store.as_ref(),
+        collection_name: &collection_name,
+        project_dir: &output_path,
+        blob_hashes: &blob_hashes,
+        job_id,
+        pending: Vec::new(),
+    };
+
+    for (path, content) in markdown_files {
+        println!("Processing Markdown file: {}", &path);
+        batch.push(project, path, content).await;
+    }
+
+    // YAML conversions run on a bounded worker pool; results are embedded in batches as they finish
+    let concurrency = env::var("INDEXING_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_LLM_CONCURRENCY);
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut workers = JoinSet::new();
+
+    let shared_project = Arc::new(project.clone());
+    for file in yaml_jobs {
+        let semaphore = Arc::clone(&semaphore);
+        let project = Arc::clone(&shared_project);
+        let output_path = output_path.clone();
+        let job_id = job_id.to_string();
 
-        if file_extension == "md" {
-            // Handle Markdown files: read content and generate embedding
-            println!("Processing Markdown file: {}", &file.path);
-            let markdown_content = std::fs::read_to_string(&file.path).unwrap();
-            embedding::process_embedding(&embedding_service, &qdrant_service, project, &file.path, &markdown_content, git_blob_hash_for_file.clone()).await;
-        } else if use_yaml && needs_update {
+        workers.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
             println!("YAML update needed for: {}", &file.path);
+            JobService::new().update_file(&job_id, &file.path, FileStage::GeneratingYaml, None);
+
             // Create a default LlmServiceConfig for the generation process
-            let llm_config = LlmServiceConfig::new();
-            let combined_content_option = yaml_management.create_yaml_with_imports(
-                &file, 
-                &project.provider, 
-                project.specific_model.as_deref(), 
-                project.yaml_model.as_deref(), 
-                Some(llm_config) // Pass config
+            let yaml_management = YamlManagement::new();
+            // Unless forced, changed files are patched from their diff where possible
+            let combined_content = yaml_management.update_yaml_with_imports(
+                &project,
+                &output_path,
+                &file,
+                Some(LlmServiceConfig::new()), // Pass config
+                !force,
             ).await;
+            (file.path, combined_content)
+        });
+    }
 
-            if let Some(combined_content) = combined_content_option {
-                // Write YAML to file
-                write(&yaml_path, combined_content.clone()).unwrap();
-
-                // Generate and store embedding, passing the git_blob_hash
-                embedding::process_embedding(&embedding_service, &qdrant_service, project, &file.path, &combined_content, git_blob_hash_for_file.clone()).await;
-            } else {
-                eprintln!("Skipping embedding for '{}' due to YAML generation failure.", &file.path);
+    while let Some(joined) = workers.join_next().await {
+        match joined {
+            Ok((path, Some(combined_content))) => {
+                let yaml_path = output_path.join(format!("{}.yml", path.replace("/", "*")));
+                if let Err(e) = write(&yaml_path, &combined_content) {
+                    job_service.update_file(job_id, &path, FileStage::Failed, Some(format!("Failed to write YAML: {}", e)));
+                    continue;
+                }
+                update_search_indexes(&output_path, &path, &combined_content);
+                batch.push(project, path, combined_content).await;
+            }
+            Ok((path, None)) => {
+                eprintln!("Skipping embedding for '{}' due to YAML generation failure.", &path);
+                job_service.update_file(job_id, &path, FileStage::Failed, Some("YAML generation failed".to_string()));
             }
+            Err(e) => eprintln!("YAML worker task failed: {}", e),
+        }
+        if job_service.is_cancelled(job_id) {
+            // Stop queued conversions; whatever finished already is still embedded below
+            workers.abort_all();
+            break;
         }
     }
 
-    // Save updated project metadata
-    let project_settings_path = Path::new(output_dir).join(&project.name).join("project_settings.json");
-    let project_settings_json = serde_json::to_string_pretty(&project).unwrap();
-    write(project_settings_path, project_settings_json).unwrap();
-}
\ No newline at end of file
+    batch.flush(project).await;
+    if job_service.is_cancelled(job_id) {
+        return Err("Cancelled".to_string());
+    }
+    Ok(())
+}
+
+/// Files waiting to be embedded and upserted together.
+struct EmbedBatch<'a> {
+    embedding_service: &'a EmbeddingService,
+    vector_store: &'a dyn VectorStore,
+    collection_name: &'a str,
+    project_dir: &'a Path,
+    blob_hashes: &'a HashMap<String, Option<String>>,
+    job_id: &'a str,
+    pending: Vec<(String, String)>,
+}
+
+impl EmbedBatch<'_> {
+    async fn push(&mut self, project: &mut Project, path: String, content: String) {
+        self.pending.push((path, content));
+        if self.pending.len() >= EMBED_BATCH_FILES {
+            self.flush(project).await;
+        }
+    }
+
+    /// Embeds everything pending, records the new metadata and saves it, so progress
+    /// survives even if the job is interrupted later on.
+    async fn flush(&mut self, project: &mut Project) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let job_service = JobService::new();
+        let files = std::mem::take(&mut self.pending);
+        for (path, _) in &files {
+            job_service.update_file(self.job_id, path, FileStage::Embedding, None);
+        }
+
+        let results = embedding::embed_files_batch(
+            self.embedding_service,
+            self.vector_store,
+            self.collection_name,
+            project,
+            &files,
+        ).await;
+
+        let mut updates = HashMap::new();
+        for (path, result) in results {
+            match result {
+                Ok(vector_id) => {
+                    let metadata = EmbeddingMetadata {
+                        file_path: path.clone(),
+                        last_updated: chrono::Utc::now(),
+                        vector_id,
+                        git_blob_hash: self.blob_hashes.get(&path).cloned().flatten(),
+                    };
+                    project.embeddings.insert(path.clone(), metadata.clone());
+                    updates.insert(path.clone(), metadata);
+                    job_service.update_file(self.job_id, &path, FileStage::Done, None);
+                }
+                Err(e) => {
+                    eprintln!("Failed to embed {}: {}", path, e);
+                    job_service.update_file(self.job_id, &path, FileStage::Failed, Some(e));
+                }
+            }
+        }
+
+        if let Err(e) = ProjectService::new().save_embedding_updates(self.project_dir, updates) {
+            eprintln!("Failed to save embedding metadata: {}", e);
+        }
+    }
+}
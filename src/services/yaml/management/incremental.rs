@@ -0,0 +1,82 @@
+// src/services/yaml/management/incremental.rs
+use crate::models::Project;
+use git2::{DiffOptions, Patch};
+use std::path::{Path, PathBuf};
+
+/// Copies of each source file as last converted to YAML. Kept with git integration too:
+/// the blob hash stored at indexing tracks the embedded source, which can be newer than
+/// the source the YAML was generated from.
+const SNAPSHOT_DIR: &str = "source_snapshots";
+
+fn snapshot_path(project_dir: &Path, source_path: &str) -> PathBuf {
+    project_dir.join(SNAPSHOT_DIR).join(source_path.replace("/", "*"))
+}
+
+/// Records the source a file's YAML was generated from.
+pub fn save_source_snapshot(project_dir: &Path, source_path: &str, content: &str) {
+    let path = snapshot_path(project_dir, source_path);
+    let result = std::fs::create_dir_all(project_dir.join(SNAPSHOT_DIR)).and_then(|_| std::fs::write(&path, content));
+    if let Err(e) = result {
+        eprintln!("Failed to save source snapshot for {}: {}", source_path, e);
+    }
+}
+
+pub fn remove_source_snapshot(project_dir: &Path, source_path: &str) {
+    let path = snapshot_path(project_dir, source_path);
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            eprintln!("Failed to remove source snapshot {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Diff from the snapshot of the source `source_path`'s current YAML was generated from
+/// to `new_content`. None when there is no snapshot to diff against or nothing changed.
+pub fn source_diff(project: &Project, project_dir: &Path, source_path: &str, new_content: &str) -> Option<String> {
+    // Diff headers read better relative to the project
+    let label = Path::new(source_path)
+        .strip_prefix(&project.source_dir)
+        .map(|relative| relative.to_string_lossy().to_string())
+        .unwrap_or_else(|_| source_path.to_string());
+    let old_content = std::fs::read_to_string(snapshot_path(project_dir, source_path)).ok()?;
+    let diff = text_diff(&old_content, new_content, &label)
+        .map_err(|e| eprintln!("No diff for {}: {}", source_path, e))
+        .ok()?;
+    if diff.trim().is_empty() {
+        None
+    } else {
+        Some(diff)
+    }
+}
+
+/// Unified diff between two versions of a file, computed without a repository.
+fn text_diff(old_content: &str, new_content: &str, file_path: &str) -> Result<String, String> {
+    let mut diff_options = DiffOptions::new();
+    diff_options.patience(true);
+    let mut patch = Patch::from_buffers(
+        old_content.as_bytes(),
+        Some(Path::new(file_path)),
+        new_content.as_bytes(),
+        Some(Path::new(file_path)),
+        Some(&mut diff_options),
+    )
+    .map_err(|e| e.to_string())?;
+    let buf = patch.to_buf().map_err(|e| e.to_string())?;
+    buf.as_str().map(str::to_string).ok_or_else(|| "Diff is not valid UTF-8".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_changed_lines_with_context() {
+        let old = "fn a() {}\n\nfn b() {\n    one();\n}\n";
+        let new = "fn a() {}\n\nfn b() {\n    two();\n}\n";
+        let diff = text_diff(old, new, "src/lib.rs").unwrap();
+        assert!(diff.contains("--- a/src/lib.rs"));
+        assert!(diff.contains("@@ -1,5 +1,5 @@"));
+        assert!(diff.contains("\n-    one();\n+    two();\n"));
+        assert!(text_diff(old, old, "src/lib.rs").unwrap().trim().is_empty());
+    }
+}
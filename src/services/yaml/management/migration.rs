@@ -0,0 +1,209 @@
+// src/services/yaml/management/migration.rs
+use crate::models::{EmbeddingConfig, EmbeddingMigration, Project};
+use crate::services::embedding_service::EmbeddingService;
+use crate::services::job_service::{FileProgress, FileStage, JobKind, JobService};
+use crate::services::project_service::ProjectService;
+use crate::services::vector_store::{self, project_collection_name, FileMetadata, VectorStore};
+use crate::services::yaml::management::embedding;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Describes a migration of `project` to `config` into a fresh collection.
+pub fn new_migration(project: &Project, config: EmbeddingConfig) -> EmbeddingMigration {
+    let started_at = Utc::now();
+    EmbeddingMigration {
+        collection: format!("project_{}_{}", project.name, started_at.timestamp()),
+        config,
+        started_at,
+    }
+}
+
+/// Queues a `MigrateEmbeddings` job for the project's pending embedding config, so it runs
+/// under the project's job lock. Does nothing if one is already queued or running.
+pub fn start_embedding_migration(project_name: &str, output_dir: &str) {
+    JobService::new().enqueue_unique(output_dir, project_name, JobKind::MigrateEmbeddings);
+}
+
+/// How a migration pass ended.
+enum MigrationOutcome {
+    Completed,
+    /// The pending migration changed (or was reverted) while this one was running
+    Superseded,
+}
+
+/// Runs the project's pending migration as job `job_id`. A migration superseded by a newer
+/// pending config while it ran is followed by that one.
+pub async fn run_migration_job(project_name: &str, output_dir: &str, job_id: &str) -> Result<(), String> {
+    while let MigrationOutcome::Superseded = run_migration(project_name, output_dir, job_id).await? {
+        JobService::new().log(job_id, "Migration was superseded by a newer embedding config");
+    }
+    Ok(())
+}
+
+/// Re-embeds every file into the pending collection, then points the project at it.
+/// Searches keep using the old collection until the final settings update.
+async fn run_migration(project_name: &str, output_dir: &str, job_id: &str) -> Result<MigrationOutcome, String> {
+    let job_service = JobService::new();
+    let project_dir = Path::new(output_dir).join(project_name);
+    let project_service = ProjectService::new();
+    let project = project_service.load_project(&project_dir)?;
+    let Some(migration) = project.pending_embedding_migration.clone() else {
+        return Ok(MigrationOutcome::Completed);
+    };
+
+    job_service.log(
+        job_id,
+        &format!(
+            "Migrating embeddings to {} ({} dims) in collection {}",
+            migration.config.model, migration.config.dimensions, migration.collection
+        ),
+    );
+
+    let vector_store = vector_store::open_project_store(&project, &project_dir)
+        .await
+        .map_err(|e| format!("Failed to open vector store: {}", e))?;
+    vector_store
+        .create_collection(&migration.collection, migration.config.dimensions as u64)
+        .await
+        .map_err(|e| format!("Failed to create collection: {}", e))?;
+
+    let embedding_service = EmbeddingService::for_project(&project_dir);
+    let mut new_vector_ids: HashMap<String, (String, DateTime<Utc>)> = HashMap::new();
+    let mut failed_files = Vec::new();
+
+    let mut file_paths: Vec<String> = project.embeddings.keys().cloned().collect();
+    file_paths.sort();
+    job_service.set_files(
+        job_id,
+        file_paths
+            .iter()
+            .map(|path| FileProgress { path: path.clone(), stage: FileStage::Pending, error: None })
+            .collect(),
+    );
+    for file_path in &file_paths {
+        if job_service.is_cancelled(job_id) {
+            // The migration stays pending; the old collection is still in use
+            return Err("Cancelled".to_string());
+        }
+        job_service.update_file(job_id, file_path, FileStage::Embedding, None);
+        match migrate_file(&embedding_service, vector_store.as_ref(), &project, &project_dir, &migration, file_path, &mut new_vector_ids).await {
+            Ok(()) => job_service.update_file(job_id, file_path, FileStage::Done, None),
+            Err(e) => {
+                job_service.update_file(job_id, file_path, FileStage::Failed, Some(e));
+                failed_files.push(file_path.clone());
+            }
+        }
+    }
+
+    // Catch up on anything that changed while the bulk pass was running
+    let latest = project_service.load_project(&project_dir)?;
+    if is_superseded(&latest, &project, &migration) {
+        let _ = vector_store.delete_collection(&migration.collection).await;
+        return Ok(MigrationOutcome::Superseded);
+    }
+
+    let changed_files: Vec<String> = latest
+        .embeddings
+        .iter()
+        .filter(|(path, metadata)| match new_vector_ids.get(*path) {
+            Some((_, embedded_at)) => metadata.last_updated > *embedded_at,
+            None => !failed_files.contains(path),
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    for file_path in &changed_files {
+        if let Err(e) = migrate_file(&embedding_service, vector_store.as_ref(), &latest, &project_dir, &migration, file_path, &mut new_vector_ids).await {
+            job_service.log(job_id, &format!("{}: {}", file_path, e));
+            failed_files.push(file_path.clone());
+        }
+    }
+
+    let removed_files: Vec<String> = new_vector_ids
+        .keys()
+        .filter(|path| !latest.embeddings.contains_key(*path))
+        .cloned()
+        .collect();
+    for file_path in removed_files {
+        if let Err(e) = vector_store.delete_file_vectors(&migration.collection, &file_path).await {
+            job_service.log(job_id, &format!("Failed to remove vectors for deleted file {}: {}", file_path, e));
+        }
+        new_vector_ids.remove(&file_path);
+    }
+
+    if !failed_files.is_empty() {
+        // Keep the migration pending so it is retried; the old collection stays in use
+        return Err(format!("{} file(s) could not be embedded: {}", failed_files.len(), failed_files.join(", ")));
+    }
+
+    // Swap: one targeted settings update moves search over to the new collection
+    let swapped = project_service.update_saved_project(&project_dir, |saved| {
+        if is_superseded(saved, &project, &migration) {
+            return None;
+        }
+        let old_collection = project_collection_name(saved);
+        saved.embedding_config = migration.config.clone();
+        saved.vector_collection = Some(migration.collection.clone());
+        saved.pending_embedding_migration = None;
+        for (file_path, (vector_id, _)) in &new_vector_ids {
+            if let Some(metadata) = saved.embeddings.get_mut(file_path) {
+                metadata.vector_id = vector_id.clone();
+            }
+        }
+        Some(old_collection)
+    })?;
+    let Some(old_collection) = swapped else {
+        let _ = vector_store.delete_collection(&migration.collection).await;
+        return Ok(MigrationOutcome::Superseded);
+    };
+    job_service.log(job_id, &format!("Migration complete, now using {}", migration.collection));
+
+    if let Err(e) = vector_store.delete_collection(&old_collection).await {
+        job_service.log(job_id, &format!("Failed to delete old collection {}: {}", old_collection, e));
+    }
+
+    Ok(MigrationOutcome::Completed)
+}
+
+/// Whether `saved` no longer wants `migration`: another config is pending (or none is), or
+/// the vector backend it was embedding into changed.
+fn is_superseded(saved: &Project, started_from: &Project, migration: &EmbeddingMigration) -> bool {
+    saved.pending_embedding_migration.as_ref().map(|m| &m.collection) != Some(&migration.collection)
+        || saved.vector_backend != started_from.vector_backend
+}
+
+/// Embeds one file into the migration collection.
+async fn migrate_file(
+    embedding_service: &EmbeddingService,
+    vector_store: &dyn VectorStore,
+    project: &Project,
+    project_dir: &Path,
+    migration: &EmbeddingMigration,
+    file_path: &str,
+    new_vector_ids: &mut HashMap<String, (String, DateTime<Utc>)>,
+) -> Result<(), String> {
+    let content = match embedding::read_content_to_embed(project, file_path, project_dir) {
+        Ok(content) => content,
+        Err(e) => {
+            // Nothing to embed (e.g. the source was deleted); don't hold up the swap for it
+            eprintln!("Skipping {} during migration: {}", file_path, e);
+            return Ok(());
+        }
+    };
+
+    let embedded_at = Utc::now();
+    let file_metadata = FileMetadata::for_file(project, file_path);
+    let vector_id = embedding::embed_into_collection(
+        embedding_service,
+        vector_store,
+        &migration.collection,
+        &migration.config,
+        file_path,
+        &content,
+        &file_metadata,
+    )
+    .await
+    .map_err(|e| format!("Failed to embed during migration: {}", e))?;
+    new_vector_ids.insert(file_path.to_string(), (vector_id, embedded_at));
+    Ok(())
+}
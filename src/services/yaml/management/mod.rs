@@ -7,13 +7,21 @@ use crate::models::{
 };
 use std::path::Path;
 pub mod generation;
+pub mod incremental;
 pub mod embedding;
 pub mod cleanup;
+pub mod migration;
+pub mod reconcile;
+pub mod symbol_chunks;
 // REMOVED: use crate::services::utils::html_utils::unescape_html; // Not needed if llm_service returns raw YAML
 use crate::services::embedding_service::EmbeddingService;
-use crate::services::qdrant_service::QdrantService;
-use crate::services::yaml::FileYamlData;
-use std::env;
+use crate::services::job_service::{JobKind, JobService};
+use crate::services::vector_store::{self, project_collection_name};
+use crate::services::yaml::{load_file_yaml, FileYamlData};
+use crate::services::yaml::processing::rust_skeleton::rust_skeleton;
+
+/// Page loads queue a reconcile job at most this often per project.
+const RECONCILE_INTERVAL_MINUTES: i64 = 30;
 
 pub struct YamlManagement {
     pub file_service: FileService,
@@ -35,30 +43,24 @@ impl YamlManagement {
         chat_model: Option<&str>, // Existing specific_model, now conceptually chat_model
         yaml_model: Option<&str>, // New parameter for YAML model
         llm_config: Option<LlmServiceConfig>, // Config now comes in directly
+        rust_skeleton_only: bool,
     ) -> Option<String> {
-
-        // Pass both chat_model (specific_model) and yaml_model and llm_config to llm_service.convert_to_yaml
-        let yaml_content_result = self.llm_service.convert_to_yaml(&project_file, provider, chat_model, yaml_model, llm_config).await;
-
         let language = Path::new(&project_file.path)
             .extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("");
 
-        match yaml_content_result {
-            Ok(yaml_content) => {
-                let (imports, _) = self.file_service.extract_imports(&project_file.content, language);
-
-                let mut combined_content = yaml_content; // This is the raw, valid YAML from LLM
+        let yaml_content_result = if rust_skeleton_only && language == "rs" {
+            // Offline: the parsed structure with doc comments as descriptions
+            rust_skeleton(&project_file.content)
+                .and_then(|skeleton| serde_yaml::to_string(&skeleton).map_err(|e| e.to_string()))
+        } else {
+            // Pass both chat_model (specific_model) and yaml_model and llm_config to llm_service.convert_to_yaml
+            self.llm_service.convert_to_yaml(project_file, provider, chat_model, yaml_model, llm_config).await
+        };
 
-                if !imports.is_empty() {
-                    let imports_string = imports.join("\n  - ");
-                    // Combine raw YAML with imports
-                    combined_content = format!("{}\n\nimports:\n  - {}", combined_content, imports_string);
-                }
-                // Return the final raw YAML string. Escaping for HTML should be done by consumers if needed.
-                Some(combined_content)
-            },
+        match yaml_content_result {
+            Ok(yaml_content) => Some(self.append_imports(project_file, yaml_content)),
             Err(e) => {
                 eprintln!("Failed to generate or validate YAML for file {}: {}", project_file.path, e);
                 None // Return None if all attempts fail or initial prompt setup fails
@@ -66,6 +68,78 @@ impl YamlManagement {
         }
     }
     
+    /// Appends the file's imports, extracted from the source, to the YAML.
+    fn append_imports(&self, project_file: &ProjectFile, yaml_content: String) -> String {
+        let language = Path::new(&project_file.path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("");
+        let (imports, _) = self.file_service.extract_imports(&project_file.content, language);
+
+        let mut combined_content = yaml_content; // This is the raw, valid YAML from LLM
+
+        if !imports.is_empty() {
+            let imports_string = imports.join("\n  - ");
+            // Combine raw YAML with imports
+            combined_content = format!("{}\n\nimports:\n  - {}", combined_content, imports_string);
+        }
+        // Return the final raw YAML string. Escaping for HTML should be done by consumers if needed.
+        combined_content
+    }
+
+    /// Generates a file's YAML with the project's models and records the source it came from.
+    /// With `allow_incremental`, when the file has YAML and a snapshot of its previous source,
+    /// the LLM patches the old YAML from the diff; otherwise, or if that fails, the file is
+    /// converted from scratch.
+    pub async fn update_yaml_with_imports(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        project_file: &ProjectFile,
+        llm_config: Option<LlmServiceConfig>,
+        allow_incremental: bool,
+    ) -> Option<String> {
+        let is_rust = Path::new(&project_file.path).extension().and_then(|ext| ext.to_str()) == Some("rs");
+        let previous_yaml = if allow_incremental { load_file_yaml(project_dir, &project_file.path) } else { None };
+        let diff = match &previous_yaml {
+            // The offline Rust skeleton costs nothing to rebuild
+            Some(_) if !(project.rust_skeleton_only && is_rust) => {
+                incremental::source_diff(project, project_dir, &project_file.path, &project_file.content)
+            }
+            _ => None,
+        };
+
+        if let (Some(previous_yaml), Some(diff)) = (previous_yaml, diff) {
+            let model_to_use = project.yaml_model.as_deref().or(project.specific_model.as_deref());
+            match self
+                .llm_service
+                .update_yaml_from_diff(project_file, &previous_yaml, &diff, &project.provider, model_to_use, llm_config.clone())
+                .await
+            {
+                Ok(yaml_content) => {
+                    incremental::save_source_snapshot(project_dir, &project_file.path, &project_file.content);
+                    return Some(self.append_imports(project_file, yaml_content));
+                }
+                Err(e) => eprintln!("Incremental YAML update failed for {}, regenerating: {}", project_file.path, e),
+            }
+        }
+
+        let combined_content = self
+            .create_yaml_with_imports(
+                project_file,
+                &project.provider,
+                project.specific_model.as_deref(),
+                project.yaml_model.as_deref(),
+                llm_config,
+                project.rust_skeleton_only,
+            )
+            .await;
+        if combined_content.is_some() {
+            incremental::save_source_snapshot(project_dir, &project_file.path, &project_file.content);
+        }
+        combined_content
+    }
+
     pub fn get_parsed_yaml_for_file_sync(
         &self,
         project: &Project,
@@ -96,62 +170,70 @@ impl YamlManagement {
     }
 
     // Move these functions from the standalone to be methods
-    pub async fn generate_yaml_files(&self, project: &mut Project, output_dir: &str, force: bool) {
-        generation::generate_yaml_files(self, project, output_dir, force).await;
+    /// Queues YAML generation and embedding for the saved project as a background job.
+    pub fn start_generation_job(&self, project_name: &str, output_dir: &str, force: bool) -> String {
+        JobService::new().enqueue(output_dir, project_name, JobKind::Generate { force })
     }
 
     pub async fn check_and_update_yaml_embeddings(&self, project: &mut Project, output_dir: &str) {
         embedding::check_and_update_yaml_embeddings(project, output_dir).await;
     }
 
-    pub fn clean_up_orphaned_files(&self, project_name: &str, orphaned_files: Vec<String>) {
-        cleanup::clean_up_orphaned_files(project_name, orphaned_files);
+    pub fn clean_up_orphaned_files(&self, project: &Project, project_dir: &Path, orphaned_files: Vec<String>) {
+        cleanup::clean_up_orphaned_files(project, project_dir, orphaned_files);
+    }
+
+    /// Queues the embedding migration job unless one is already queued or running.
+    pub fn start_embedding_migration(&self, project_name: &str, output_dir: &str) {
+        migration::start_embedding_migration(project_name, output_dir);
+    }
+
+    /// Queues a reconcile job unless one is already queued or running for the project, or the
+    /// last one (finished or failed) was queued less than `RECONCILE_INTERVAL_MINUTES` ago.
+    pub fn queue_reconcile(&self, project_name: &str, output_dir: &str) {
+        let job_service = JobService::new();
+        let recently_queued = job_service
+            .latest_job_for_project(project_name, JobKind::Reconcile.name())
+            .is_some_and(|job| chrono::Utc::now() - job.queued_at < chrono::Duration::minutes(RECONCILE_INTERVAL_MINUTES));
+        if !recently_queued {
+            job_service.enqueue_unique(output_dir, project_name, JobKind::Reconcile);
+        }
     }
 
     pub async fn regenerate_embedding(&self, project: &mut Project, file_path: &str, output_dir: &str) {
-        let embedding_service = EmbeddingService::new();
-        let qdrant_server_url = env::var("QDRANT_SERVER_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
+        let output_path = Path::new(output_dir).join(&project.name);
+        let embedding_service = EmbeddingService::for_project(&output_path);
 
-        let qdrant_service = match QdrantService::new(&qdrant_server_url, 1536).await {
-            Ok(service) => service,
+        let vector_store = match vector_store::open_project_store(project, &output_path).await {
+            Ok(store) => store,
             Err(e) => {
-                eprintln!("Failed to connect to Qdrant: {}", e);
+                eprintln!("Failed to open vector store: {}", e);
                 return;
             }
         };
 
+        let collection_name = project_collection_name(project);
+        // The collection may not exist yet, e.g. right after switching vector backends
+        if let Err(e) = vector_store.create_collection(&collection_name, project.embedding_config.dimensions as u64).await {
+            eprintln!("Failed to create collection: {}", e);
+            return;
+        }
+
         // **Delete existing embedding**
-        if let Err(e) = qdrant_service.delete_file_vectors(&project.name, file_path).await {
+        if let Err(e) = vector_store.delete_file_vectors(&collection_name, file_path).await {
             eprintln!("Failed to delete existing vectors: {}", e);
             // Consider whether to return early here, depending on your error handling policy
             // If deleting the old embedding fails, it might be best to avoid creating a new one
             return;
         }
 
-        let output_path = Path::new(output_dir).join(&project.name);
-        let yaml_path = output_path.join(format!("{}.yml", file_path.replace("/", "*")));
-        let use_yaml = project.file_yaml_override.get(file_path).map(|&b| b).unwrap_or(project.default_use_yaml);
-
-        let content_to_embed: String;
-        if use_yaml {
-            // Read the YAML content
-            content_to_embed = match std::fs::read_to_string(&yaml_path) {
-                Ok(yaml_content) => yaml_content,
-                Err(e) => {
-                    eprintln!("Error reading YAML file: {}", e);
-                    return;
-                }
-            };
-        } else {
-            // Read the original source file content
-            content_to_embed = match std::fs::read_to_string(file_path) {
-                Ok(source_content) => source_content,
-                Err(e) => {
-                    eprintln!("Error reading original source file: {}", e);
-                    return;
-                }
-            };
-        }
+        let content_to_embed = match embedding::read_content_to_embed(project, file_path, &output_path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
 
         let mut current_blob_hash: Option<String> = None;
         let source_file_abs_path = Path::new(file_path);
@@ -175,7 +257,7 @@ impl YamlManagement {
             }
         }
 
-        embedding::process_embedding(&embedding_service, &qdrant_service, project, file_path, &content_to_embed, current_blob_hash).await;
+        embedding::process_embedding(&embedding_service, vector_store.as_ref(), project, file_path, &content_to_embed, current_blob_hash).await;
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+// src/services/yaml/management/reconcile.rs
+use crate::models::Project;
+use crate::services::embedding_service::EmbeddingService;
+use crate::services::project_service::ProjectService;
+use crate::services::vector_store::{self, payload_string, project_collection_name, SYMBOL_POINT};
+use crate::services::yaml::management::embedding;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OrphanPoint {
+    pub id: String,
+    pub file_path: String,
+    pub point_type: String,
+}
+
+/// Differences found between `Project.embeddings` and the points in the collection.
+#[derive(Serialize, Debug, Clone)]
+pub struct ReconcileReport {
+    pub project: String,
+    pub collection: String,
+    pub checked_files: usize,
+    pub points_in_collection: usize,
+    /// Files whose `vector_id` has no matching point
+    pub missing_files: Vec<String>,
+    pub re_embedded: Vec<String>,
+    pub re_embed_failed: Vec<String>,
+    /// Points that belong to no tracked file, or to an outdated embedding of one
+    pub orphan_points: Vec<OrphanPoint>,
+    pub deleted_orphans: usize,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Compares the project's embedding metadata with the collection, re-embeds files whose
+/// point is missing and deletes orphan points. Updates `project.embeddings` for re-embedded
+/// files and writes the report to `<project_dir>/reconcile_report.json`.
+pub async fn reconcile_embeddings(project: &mut Project, project_dir: &Path) -> Result<ReconcileReport, String> {
+    let collection_name = project_collection_name(project);
+    let vector_store = vector_store::open_project_store(project, project_dir)
+        .await
+        .map_err(|e| format!("Failed to open vector store: {}", e))?;
+
+    // A wiped store has no collection at all; recreate it so every file shows up as missing
+    vector_store
+        .create_collection(&collection_name, project.embedding_config.dimensions as u64)
+        .await
+        .map_err(|e| format!("Failed to create collection: {}", e))?;
+
+    let points = vector_store
+        .scroll_payloads(&collection_name)
+        .await
+        .map_err(|e| format!("Failed to read points: {}", e))?;
+
+    let file_point_ids: HashSet<&str> = points
+        .iter()
+        .filter(|point| payload_string(&point.payload, "point_type") != SYMBOL_POINT)
+        .map(|point| point.id.as_str())
+        .collect();
+
+    let mut missing_files: Vec<String> = project
+        .embeddings
+        .iter()
+        .filter(|(_, metadata)| !file_point_ids.contains(metadata.vector_id.as_str()))
+        .map(|(file_path, _)| file_path.clone())
+        .collect();
+    missing_files.sort();
+
+    let mut orphan_points = Vec::new();
+    for point in &points {
+        let file_path = payload_string(&point.payload, "file_path");
+        let point_type = payload_string(&point.payload, "point_type");
+        let is_orphan = match project.embeddings.get(&file_path) {
+            None => true,
+            Some(metadata) if point_type == SYMBOL_POINT => {
+                // Older symbol points have no file_point_id and can't be checked
+                let file_point_id = payload_string(&point.payload, "file_point_id");
+                !file_point_id.is_empty() && file_point_id != metadata.vector_id
+            }
+            Some(metadata) => point.id != metadata.vector_id,
+        };
+        // Re-embedding a missing file replaces all of its points anyway
+        if is_orphan && !missing_files.contains(&file_path) {
+            orphan_points.push(OrphanPoint {
+                id: point.id.clone(),
+                file_path,
+                point_type: if point_type.is_empty() { "file".to_string() } else { point_type },
+            });
+        }
+    }
+
+    let mut deleted_orphans = 0;
+    if !orphan_points.is_empty() {
+        let ids: Vec<String> = orphan_points.iter().map(|p| p.id.clone()).collect();
+        match vector_store.delete_points(&collection_name, ids).await {
+            Ok(_) => deleted_orphans = orphan_points.len(),
+            Err(e) => eprintln!("Failed to delete orphan points: {}", e),
+        }
+    }
+
+    let embedding_service = EmbeddingService::for_project(project_dir);
+    let mut re_embedded = Vec::new();
+    let mut re_embed_failed = Vec::new();
+    for file_path in &missing_files {
+        let content = match embedding::read_content_to_embed(project, file_path, project_dir) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Cannot re-embed {}: {}", file_path, e);
+                re_embed_failed.push(file_path.clone());
+                continue;
+            }
+        };
+
+        let git_blob_hash = project.embeddings.get(file_path).and_then(|m| m.git_blob_hash.clone());
+        let previous_vector_id = project.embeddings.get(file_path).map(|m| m.vector_id.clone());
+        embedding::process_embedding(&embedding_service, vector_store.as_ref(), project, file_path, &content, git_blob_hash).await;
+
+        // process_embedding only touches the metadata when the new point was stored
+        if project.embeddings.get(file_path).map(|m| m.vector_id.clone()) != previous_vector_id {
+            re_embedded.push(file_path.clone());
+        } else {
+            re_embed_failed.push(file_path.clone());
+        }
+    }
+
+    let report = ReconcileReport {
+        project: project.name.clone(),
+        collection: collection_name,
+        checked_files: project.embeddings.len(),
+        points_in_collection: points.len(),
+        missing_files,
+        re_embedded,
+        re_embed_failed,
+        orphan_points,
+        deleted_orphans,
+        finished_at: Utc::now(),
+    };
+
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(project_dir.join("reconcile_report.json"), json) {
+                eprintln!("Failed to write reconcile report: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize reconcile report: {}", e),
+    }
+
+    Ok(report)
+}
+
+/// Runs `reconcile_embeddings` against the saved project and saves only the metadata
+/// it changed, so edits made while it ran are kept.
+pub async fn reconcile_saved_project(project_name: &str, output_dir: &str) -> Result<ReconcileReport, String> {
+    let project_dir = Path::new(output_dir).join(project_name);
+    let project_service = ProjectService::new();
+    let mut project = project_service.load_project(&project_dir)?;
+
+    let vector_ids_before: HashMap<String, String> = project
+        .embeddings
+        .iter()
+        .map(|(path, m)| (path.clone(), m.vector_id.clone()))
+        .collect();
+
+    let report = reconcile_embeddings(&mut project, &project_dir).await?;
+
+    let updates = project
+        .embeddings
+        .into_iter()
+        .filter(|(path, m)| vector_ids_before.get(path) != Some(&m.vector_id))
+        .collect();
+    project_service
+        .save_embedding_updates(&project_dir, updates)
+        .map_err(|e| format!("Failed to save project after reconcile: {}", e))?;
+
+    Ok(report)
+}
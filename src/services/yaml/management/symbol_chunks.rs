@@ -0,0 +1,127 @@
+// src/services/yaml/management/symbol_chunks.rs
+use crate::services::yaml::{FileYamlData, Function};
+
+/// A single function, class method or data structure pulled out of a file's YAML
+/// summary so it can be embedded as its own vector point.
+#[derive(Debug, Clone)]
+pub struct SymbolChunk {
+    pub name: String,
+    pub kind: String,                  // "function", "method" or "data_structure"
+    pub parent_symbol: Option<String>, // Owning class for methods
+    pub text: String,                  // Text that gets embedded
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+/// Parses embedded content as `FileYamlData`. Returns None for raw source or markdown,
+/// which only get a file-level point.
+pub fn parse_yaml_content(content: &str) -> Option<FileYamlData> {
+    serde_yaml::from_str::<FileYamlData>(content).ok()
+}
+
+/// Builds one chunk per function, class method and data structure in the file.
+pub fn build_symbol_chunks(file_path: &str, yaml_data: &FileYamlData) -> Vec<SymbolChunk> {
+    let mut chunks = Vec::new();
+
+    for function in &yaml_data.functions {
+        chunks.push(SymbolChunk {
+            name: function.name.clone(),
+            kind: "function".to_string(),
+            parent_symbol: None,
+            text: function_text(file_path, None, "function", function),
+            start_line: function.start_line,
+            end_line: function.end_line,
+        });
+    }
+
+    for class in &yaml_data.classes {
+        for method in &class.methods {
+            chunks.push(SymbolChunk {
+                name: method.name.clone(),
+                kind: "method".to_string(),
+                parent_symbol: Some(class.name.clone()),
+                text: function_text(file_path, Some(&class.name), "method", method),
+                start_line: method.start_line,
+                end_line: method.end_line,
+            });
+        }
+    }
+
+    for data_structure in &yaml_data.data_structures {
+        let mut text = format!(
+            "File: {}\n{} {}\n",
+            file_path, data_structure.ds_type, data_structure.name
+        );
+        if let Some(description) = &data_structure.description {
+            text.push_str(&format!("Description: {}\n", description));
+        }
+        if !data_structure.structure.is_empty() {
+            let fields: Vec<&str> = data_structure.structure.keys().map(|k| k.as_str()).collect();
+            text.push_str(&format!("Fields: {}\n", fields.join(", ")));
+        }
+        chunks.push(SymbolChunk {
+            name: data_structure.name.clone(),
+            kind: "data_structure".to_string(),
+            parent_symbol: None,
+            text,
+            start_line: data_structure.start_line,
+            end_line: data_structure.end_line,
+        });
+    }
+
+    chunks
+}
+
+/// Embedded text for a function or method. The declaration reads `fn name(..)` in Rust
+/// files and is introduced by `kind` elsewhere, so other languages don't look like Rust.
+fn function_text(file_path: &str, class_name: Option<&str>, kind: &str, function: &Function) -> String {
+    let qualified_name = match class_name {
+        Some(class_name) => format!("{}.{}", class_name, function.name),
+        None => function.name.clone(),
+    };
+    let params: Vec<String> = function
+        .parameters
+        .iter()
+        .map(|p| format!("{}: {}", p.name, p.param_type))
+        .collect();
+
+    let keyword = if file_path.ends_with(".rs") { "fn" } else { kind };
+    let mut text = format!("File: {}\n{} {}({})", file_path, keyword, qualified_name, params.join(", "));
+    if let Some(return_type) = &function.return_type {
+        text.push_str(&format!(" -> {}", return_type));
+    }
+    text.push('\n');
+    if let Some(description) = &function.description {
+        text.push_str(&format!("Description: {}\n", description));
+    }
+    if !function.calls.is_empty() {
+        text.push_str(&format!("Calls: {}\n", function.calls.join(", ")));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declarations_use_the_file_language() {
+        let yaml: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Loads things.
+functions:
+  - name: load
+classes:
+  - name: Loader
+    methods:
+      - name: save
+"#,
+        )
+        .unwrap();
+        let texts = |path: &str| -> Vec<String> {
+            build_symbol_chunks(path, &yaml).into_iter().map(|chunk| chunk.text.lines().nth(1).unwrap().to_string()).collect()
+        };
+        assert_eq!(texts("src/loader.rs"), vec!["fn load()", "fn Loader.save()"]);
+        assert_eq!(texts("app/loader.py"), vec!["function load()", "method Loader.save()"]);
+    }
+}
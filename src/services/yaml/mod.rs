@@ -1,6 +1,8 @@
 // src/services/yaml/mod.rs
 pub mod management;
 pub mod processing;
+pub mod keyword_index;
+pub mod symbol_index;
 
 pub use management::YamlManagement;
 pub use processing::YamlProcessing;
@@ -8,8 +10,6 @@ pub use processing::YamlProcessing;
 use crate::models::Project;
 use std::path::Path;
 use serde::{Deserialize, Serialize}; // For FileYamlData
-use std::collections::{ HashMap, HashSet }; // For FileYamlData (if needed, currently not)
-use crate::services::file::FileService; // To read YAML files
 
 
 use std::collections::BTreeMap;
@@ -17,24 +17,33 @@ use std::collections::BTreeMap;
 #[derive(Debug, Serialize, Deserialize, Clone, Default)] 
 pub struct FileYamlData {
     pub description: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub functions: Vec<Function>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub classes: Vec<Class>,
-    #[serde(default, rename = "data_structures")]
-    pub data_structures: Vec<DataStructure>
+    #[serde(default, rename = "data_structures", skip_serializing_if = "Vec::is_empty")]
+    pub data_structures: Vec<DataStructure>,
+    /// Appended from the source by `create_yaml_with_imports`, not written by the LLM
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Function {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub parameters: Vec<Parameter>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub return_type: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub calls: Vec<String>,
+    /// 1-based line range in the source file, where known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,18 +51,26 @@ pub struct Parameter {
     pub name: String,
     #[serde(rename = "type")]
     pub param_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Class {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub inherits: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub methods: Vec<Function>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub properties: Vec<Parameter>, // Reusing Parameter struct for properties
+    /// 1-based line range in the source file, where known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -61,9 +78,33 @@ pub struct DataStructure {
     pub name: String,
     #[serde(rename = "type")]
     pub ds_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub structure: BTreeMap<String, serde_yaml::Value>,
+    /// 1-based line range in the source file, where known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+}
+
+/// Parsed YAML summary for a source file, if it has one that parses.
+pub fn load_file_yaml(project_dir: &Path, source_path: &str) -> Option<FileYamlData> {
+    let yaml_path = project_dir.join(format!("{}.yml", source_path.replace("/", "*")));
+    let content = std::fs::read_to_string(yaml_path).ok()?;
+    serde_yaml::from_str(&content).ok()
+}
+
+/// Updates the keyword and symbol indexes after a file's YAML was (re)written.
+pub fn update_search_indexes(project_dir: &Path, source_path: &str, yaml_content: &str) {
+    keyword_index::update_file(project_dir, source_path, yaml_content);
+    symbol_index::update_file(project_dir, source_path, yaml_content);
+}
+
+pub fn remove_from_search_indexes(project_dir: &Path, source_path: &str) {
+    keyword_index::remove_file(project_dir, source_path);
+    symbol_index::remove_file(project_dir, source_path);
 }
 
 pub struct YamlService {
@@ -80,16 +121,17 @@ impl YamlService {
     }
 
     // Methods that delegate to appropriate modules
-    pub async fn save_yaml_files(&self, project: &mut Project, output_dir: &str, force: bool) {
-        self.management.generate_yaml_files(project, output_dir, force).await;
+    /// Generates YAML files and embeddings in the background; returns the job id.
+    pub fn save_yaml_files(&self, project_name: &str, output_dir: &str, force: bool) -> String {
+        self.management.start_generation_job(project_name, output_dir, force)
     }
 
     pub async fn check_and_update_yaml_files(&self, project: &mut Project, output_dir: &str) {
         self.management.check_and_update_yaml_embeddings(project, output_dir).await;
     }
 
-    pub fn clean_up_orphaned_files(&self, project_name: &str, orphaned_files: Vec<String>) {
-        self.management.clean_up_orphaned_files(project_name, orphaned_files);
+    pub fn clean_up_orphaned_files(&self, project: &Project, project_dir: &Path, orphaned_files: Vec<String>) {
+        self.management.clean_up_orphaned_files(project, project_dir, orphaned_files);
     }
 
     pub fn process_yaml_files(&self, output_dir: &Path, project_name: &str, project: &mut Project)
@@ -98,82 +140,14 @@ impl YamlService {
         self.processing.process_yaml_files(output_dir, project_name, project)
     }
 
-    /// BM25F Placeholder: Performs a keyword search over YAML data.
-    /// In a real implementation, this would be a proper BM25F ranking.
-    /// For this example, it's a simple contains-based keyword match with basic scoring.
-    pub async fn bm25f_search(
+    /// BM25F keyword search over the project's YAML summaries. `project_dir` is the
+    /// project's output dir; results are source paths, best first.
+    pub fn bm25f_search(
         &self,
-        project: &Project,
         query_text: &str,
-        output_dir: &Path,
+        project_dir: &Path,
         num_results: usize,
     ) -> Result<Vec<(String, f32)>, String> {
-        let file_service = FileService {};
-        let yaml_files_dir = output_dir;
-
-        let query_keywords: HashSet<String> = query_text
-            .to_lowercase()
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
-
-        if query_keywords.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let mut scores: HashMap<String, f32> = HashMap::new();
-
-        // Iterate through all YAML files generated for the project
-        if let Ok(entries) = std::fs::read_dir(&yaml_files_dir) {
-            for entry in entries {
-                let entry = entry.map_err(|e| format!("Error reading directory entry: {}", e))?;
-                let path = entry.path();
-
-                if path.extension().map_or(false, |ext| ext == "yaml" || ext == "yml") {
-                    let file_content = file_service.read_specific_file(project, &path.to_string_lossy());
-                    let yaml_data: FileYamlData = serde_yaml::from_str(&file_content.unwrap())
-                        .map_err(|e| format!("Failed to parse YAML file {}: {}", path.display(), e))?;
-
-                    let mut current_score = 0.0;
-
-                    // Score based on keyword presence in different fields
-                    for keyword in &query_keywords {
-                        // Summary (High weight)
-                        if yaml_data.description.to_lowercase().contains(keyword) {
-                            current_score += 3.0;
-                        }
-                        // Calls (Medium weight) from functions and class methods
-                        let has_call = yaml_data.functions.iter()
-                            .flat_map(|f| f.calls.iter())
-                            .any(|c| c.to_lowercase().contains(keyword))
-                            || yaml_data.classes.iter()
-                                .flat_map(|c| c.methods.iter())
-                                .flat_map(|m| m.calls.iter())
-                                .any(|c| c.to_lowercase().contains(keyword));
-                        if has_call {
-                            current_score += 2.0;
-                        }
-                        // File path (Low weight) - use YAML file path on disk
-                        if path.to_string_lossy().to_lowercase().contains(keyword) {
-                            current_score += 0.5;
-                        }
-                    }
-
-                    if current_score > 0.0 {
-                        scores.insert(path.to_string_lossy().into_owned(), current_score);
-                    }
-                }
-            }
-        } else {
-            // Directory might not exist yet, which is fine if no YAMLs have been generated
-            println!("YAML files directory not found: {}. No YAML-based search will be performed.", yaml_files_dir.display());
-            return Ok(Vec::new());
-        }
-
-        // Sort by score and take top results
-        let mut sorted_scores: Vec<(String, f32)> = scores.into_iter().collect();
-        sorted_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        
-        Ok(sorted_scores.into_iter().take(num_results).collect())
+        Ok(keyword_index::search(project_dir, query_text, num_results))
     }
 }
\ No newline at end of file
@@ -0,0 +1,347 @@
+// src/services/yaml/processing/chunking.rs
+use crate::services::yaml::{Class, DataStructure, FileYamlData, Function};
+
+/// Characters of source sent to the LLM in one YAML conversion; larger files are split.
+/// Overridden by `YAML_CHUNK_CHARS`.
+pub const DEFAULT_MAX_CHUNK_CHARS: usize = 40_000;
+
+/// A run of whole lines from a source file. Lines are 1-based and inclusive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceChunk {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+    /// Declaration lines of the items this chunk sits inside, outermost first, when it
+    /// starts in the middle of an item that was too large for one chunk
+    pub enclosing: Option<String>,
+}
+
+/// Splits `content` into chunks of at most `max_chars`, cutting only where a new item
+/// starts: a line at the outermost indentation that follows a blank line or a closing
+/// brace. An item too large on its own is split between its members, one indentation
+/// level in, and only as a last resort between arbitrary lines.
+pub fn split_source(content: &str, max_chars: usize) -> Vec<SourceChunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    if content.len() <= max_chars || lines.len() < 2 {
+        return vec![SourceChunk {
+            start_line: 1,
+            end_line: lines.len().max(1),
+            text: content.to_string(),
+            enclosing: None,
+        }];
+    }
+    split_lines(&lines, 0, lines.len(), max_chars)
+        .into_iter()
+        .map(|range| SourceChunk {
+            start_line: range.start + 1,
+            end_line: range.end,
+            text: lines[range.start..range.end].join("\n"),
+            enclosing: (!range.enclosing.is_empty()).then(|| range.enclosing.join("\n")),
+        })
+        .collect()
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn size(lines: &[&str], start: usize, end: usize) -> usize {
+    lines[start..end].iter().map(|line| line.len() + 1).sum()
+}
+
+/// Whether an item can start at `lines[index]`, and at which indentation.
+fn item_start(lines: &[&str], index: usize) -> Option<usize> {
+    let line = lines[index];
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with(['}', ')', ']']) {
+        return None;
+    }
+    let previous = lines[index - 1].trim();
+    (previous.is_empty() || previous.starts_with('}') || previous == "end").then(|| indentation(line))
+}
+
+/// The line declaring the item in `lines[start..end]`, skipping the comments, attributes
+/// and decorators above it.
+fn declaration_line(lines: &[&str], start: usize, end: usize) -> String {
+    let is_preamble = |line: &&str| {
+        let trimmed = line.trim();
+        ["//", "/*", "*", "#", "@", "--"].iter().any(|prefix| trimmed.starts_with(prefix))
+    };
+    lines[start..end].iter().find(|line| !is_preamble(line)).unwrap_or(&lines[start]).to_string()
+}
+
+/// A half-open `[start, end)` line range and the declarations of the items it is inside.
+struct LineRange {
+    start: usize,
+    end: usize,
+    enclosing: Vec<String>,
+}
+
+impl LineRange {
+    fn new((start, end): (usize, usize)) -> Self {
+        LineRange { start, end, enclosing: Vec::new() }
+    }
+}
+
+/// Line ranges covering `lines[start..end]`, each within `max_chars` unless a single line is
+/// longer. Ranges cut out of an oversized item, other than its first, record its declaration.
+fn split_lines(lines: &[&str], start: usize, end: usize, max_chars: usize) -> Vec<LineRange> {
+    if size(lines, start, end) <= max_chars || end - start < 2 {
+        return vec![LineRange::new((start, end))];
+    }
+
+    let starts: Vec<(usize, usize)> = (start + 1..end).filter_map(|i| item_start(lines, i).map(|indent| (i, indent))).collect();
+    let pieces: Vec<(usize, usize)> = match starts.iter().map(|(_, indent)| *indent).min() {
+        Some(outermost) => {
+            let mut bounds = vec![start];
+            bounds.extend(starts.iter().filter(|(_, indent)| *indent == outermost).map(|(i, _)| *i));
+            bounds.push(end);
+            bounds.windows(2).map(|pair| (pair[0], pair[1])).collect()
+        }
+        // Nowhere to cut cleanly: fall back to one line per piece
+        None => (start..end).map(|i| (i, i + 1)).collect(),
+    };
+
+    let mut ranges: Vec<LineRange> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for (piece_start, piece_end) in pieces {
+        if size(lines, piece_start, piece_end) > max_chars {
+            ranges.extend(current.take().map(LineRange::new));
+            let declaration = declaration_line(lines, piece_start, piece_end);
+            for mut range in split_lines(lines, piece_start, piece_end, max_chars) {
+                if range.start != piece_start {
+                    range.enclosing.insert(0, declaration.clone());
+                }
+                ranges.push(range);
+            }
+            continue;
+        }
+        current = match current {
+            Some((current_start, _)) if size(lines, current_start, piece_end) <= max_chars => Some((current_start, piece_end)),
+            Some(full) => {
+                ranges.push(LineRange::new(full));
+                Some((piece_start, piece_end))
+            }
+            None => Some((piece_start, piece_end)),
+        };
+    }
+    ranges.extend(current.map(LineRange::new));
+    ranges
+}
+
+/// Combines the YAML of a file's chunks, in order. Symbols that show up in more than one
+/// chunk (a class split across chunks, or an item repeated for context) are merged: the
+/// first description wins, calls, methods and fields are unioned. A class without `inherits`
+/// merges into the earlier one of that name, since a chunk continuing a class may not see its
+/// bases. The file description is left empty for the caller to write from the chunks' descriptions.
+pub fn merge_chunk_yaml(parts: Vec<FileYamlData>) -> FileYamlData {
+    let mut merged = FileYamlData::default();
+    for part in parts {
+        for function in part.functions {
+            merge_function(&mut merged.functions, function);
+        }
+        for class in part.classes {
+            let same = |other: &&mut Class| {
+                other.name == class.name && (class.inherits.is_none() || other.inherits == class.inherits)
+            };
+            match merged.classes.iter_mut().find(same) {
+                Some(existing) => merge_class(existing, class),
+                None => merged.classes.push(class),
+            }
+        }
+        for data_structure in part.data_structures {
+            match merged.data_structures.iter_mut().find(|other| other.name == data_structure.name) {
+                Some(existing) => merge_data_structure(existing, data_structure),
+                None => merged.data_structures.push(data_structure),
+            }
+        }
+        for import in part.imports {
+            if !merged.imports.contains(&import) {
+                merged.imports.push(import);
+            }
+        }
+    }
+    merged
+}
+
+/// Functions are the same when their names match and their start lines don't disagree,
+/// so overloads at different lines stay separate.
+fn merge_function(functions: &mut Vec<Function>, function: Function) {
+    let same = |other: &&mut Function| {
+        other.name == function.name
+            && (other.start_line.is_none() || function.start_line.is_none() || other.start_line == function.start_line)
+    };
+    let Some(existing) = functions.iter_mut().find(same) else {
+        functions.push(function);
+        return;
+    };
+    existing.description = existing.description.take().or(function.description);
+    existing.return_type = existing.return_type.take().or(function.return_type);
+    if existing.parameters.is_empty() {
+        existing.parameters = function.parameters;
+    }
+    for call in function.calls {
+        if !existing.calls.contains(&call) {
+            existing.calls.push(call);
+        }
+    }
+    existing.start_line = existing.start_line.or(function.start_line);
+    existing.end_line = existing.end_line.max(function.end_line);
+}
+
+fn merge_class(existing: &mut Class, class: Class) {
+    existing.description = existing.description.take().or(class.description);
+    for method in class.methods {
+        merge_function(&mut existing.methods, method);
+    }
+    for property in class.properties {
+        if !existing.properties.iter().any(|other| other.name == property.name) {
+            existing.properties.push(property);
+        }
+    }
+    existing.start_line = match (existing.start_line, class.start_line) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    existing.end_line = existing.end_line.max(class.end_line);
+}
+
+fn merge_data_structure(existing: &mut DataStructure, data_structure: DataStructure) {
+    existing.description = existing.description.take().or(data_structure.description);
+    for (field, field_type) in data_structure.structure {
+        existing.structure.entry(field).or_insert(field_type);
+    }
+    existing.start_line = existing.start_line.or(data_structure.start_line);
+    existing.end_line = existing.end_line.max(data_structure.end_line);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> String {
+        let mut source = String::from("import os\nimport sys\n");
+        for name in ["alpha", "beta", "gamma"] {
+            source.push_str(&format!("\ndef {}(x):\n    y = x + 1\n\n    return y\n", name));
+        }
+        source.push_str("\nclass Big:\n    def one(self):\n        pass\n\n    def two(self):\n        pass\n");
+        source
+    }
+
+    #[test]
+    fn small_files_are_one_chunk() {
+        let chunks = split_source("fn main() {}\n", 1_000);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 1));
+    }
+
+    #[test]
+    fn splits_at_top_level_items_and_covers_every_line() {
+        let source = source();
+        let chunks = split_source(&source, 70);
+
+        let starts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.lines().next().unwrap()).collect();
+        assert_eq!(starts, vec!["import os", "def beta(x):", "def gamma(x):", "class Big:", "    def two(self):"]);
+        let enclosing: Vec<Option<&str>> = chunks.iter().map(|chunk| chunk.enclosing.as_deref()).collect();
+        assert_eq!(enclosing, vec![None, None, None, None, Some("class Big:")]);
+        let rejoined: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(rejoined.join("\n"), source.lines().collect::<Vec<_>>().join("\n"));
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end_line + 1, pair[1].start_line);
+        }
+        assert_eq!(chunks.last().unwrap().end_line, source.lines().count());
+    }
+
+    #[test]
+    fn merges_symbols_repeated_across_chunks() {
+        let first: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Part one.
+functions:
+  - name: load
+    description: Loads.
+    calls: [read]
+    start_line: 3
+classes:
+  - name: Big
+    description: A big class.
+    methods:
+      - name: one
+    start_line: 10
+    end_line: 40
+"#,
+        )
+        .unwrap();
+        let second: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Part two.
+functions:
+  - name: load
+    calls: [read, parse]
+  - name: load
+    description: An overload further down.
+    start_line: 90
+classes:
+  - name: Big
+    methods:
+      - name: two
+    start_line: 41
+    end_line: 80
+"#,
+        )
+        .unwrap();
+        let merged = merge_chunk_yaml(vec![first, second]);
+
+        assert!(merged.description.is_empty());
+        assert_eq!(merged.functions.len(), 2);
+        assert_eq!(merged.functions[0].description.as_deref(), Some("Loads."));
+        assert_eq!(merged.functions[0].calls, vec!["read", "parse"]);
+        assert_eq!(merged.functions[1].start_line, Some(90));
+        let big = &merged.classes[0];
+        assert_eq!(big.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["one", "two"]);
+        assert_eq!((big.start_line, big.end_line), (Some(10), Some(80)));
+    }
+
+    #[test]
+    fn methods_of_a_continued_class_stay_in_the_class() {
+        let first: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Part one.
+classes:
+  - name: Big
+    inherits: Base
+    methods:
+      - name: one
+        start_line: 11
+    start_line: 10
+"#,
+        )
+        .unwrap();
+        // The second chunk starts at `    def two(self):` and was told it is inside `class Big(Base):`
+        let second: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Part two.
+classes:
+  - name: Big
+    methods:
+      - name: two
+        start_line: 14
+      - name: three
+        start_line: 17
+    end_line: 18
+"#,
+        )
+        .unwrap();
+        let merged = merge_chunk_yaml(vec![first, second]);
+
+        assert!(merged.functions.is_empty());
+        assert_eq!(merged.classes.len(), 1);
+        let big = &merged.classes[0];
+        assert_eq!(big.inherits.as_deref(), Some("Base"));
+        assert_eq!(big.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["one", "two", "three"]);
+        assert_eq!((big.start_line, big.end_line), (Some(10), Some(18)));
+    }
+}
@@ -0,0 +1,144 @@
+// src/services/yaml/processing/line_ranges.rs
+use crate::services::yaml::FileYamlData;
+
+/// Words that introduce a declaration in the languages we summarize.
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "fn", "def", "function", "func", "fun", "class", "struct", "enum", "interface", "trait", "type", "impl",
+    "record", "module", "object", "const", "let", "var", "val", "sub", "procedure",
+];
+/// Words that put a name followed by `(` into a statement rather than a declaration.
+const CONTROL_KEYWORDS: &[&str] = &["if", "while", "for", "return", "match", "switch", "await", "new", "elif", "else"];
+
+/// Checks the LLM's `start_line`/`end_line` of every symbol against the source. A start line
+/// that doesn't declare the symbol is moved to the line that does (the end moves with it);
+/// a symbol whose declaration can't be found loses its range.
+pub fn verify_line_ranges(yaml: &mut FileYamlData, source: &str) {
+    let lines: Vec<&str> = source.lines().collect();
+    let whole_file = (1, lines.len());
+
+    for function in &mut yaml.functions {
+        (function.start_line, function.end_line) =
+            verify_range(&function.name, function.start_line, function.end_line, &lines, whole_file);
+    }
+    for data_structure in &mut yaml.data_structures {
+        (data_structure.start_line, data_structure.end_line) =
+            verify_range(&data_structure.name, data_structure.start_line, data_structure.end_line, &lines, whole_file);
+    }
+    for class in &mut yaml.classes {
+        (class.start_line, class.end_line) = verify_range(&class.name, class.start_line, class.end_line, &lines, whole_file);
+        // Methods are looked for inside their class when its range is known
+        let scope = match (class.start_line, class.end_line) {
+            (Some(start), Some(end)) => (start, end),
+            _ => whole_file,
+        };
+        for method in &mut class.methods {
+            (method.start_line, method.end_line) = verify_range(&method.name, method.start_line, method.end_line, &lines, scope);
+        }
+    }
+}
+
+fn verify_range(
+    name: &str,
+    start: Option<usize>,
+    end: Option<usize>,
+    lines: &[&str],
+    (scope_start, scope_end): (usize, usize),
+) -> (Option<usize>, Option<usize>) {
+    let in_scope = |line: usize| line >= scope_start && line <= scope_end;
+    let declares_at = |line: usize| in_scope(line) && declares(lines[line - 1], name);
+
+    let verified_start = match start {
+        Some(line) if declares_at(line) => Some(line),
+        _ => (scope_start..=scope_end).find(|&line| declares_at(line)),
+    };
+    let Some(verified_start) = verified_start else {
+        return (None, None);
+    };
+    let verified_end = end
+        .zip(start)
+        .map(|(end, start)| (end + verified_start).checked_sub(start))
+        .and_then(|end| end.filter(|&end| end >= verified_start && end <= lines.len()));
+    (Some(verified_start), verified_end)
+}
+
+/// Whether `line` looks like the declaration of `name`: the name follows a declaration
+/// keyword (`pub async fn name`, `class Name(Base):`), is assigned a function
+/// (`name = (a) =>`), or is followed by a parameter list on a line opening a block
+/// (`public void name(int a) {`).
+fn declares(line: &str, name: &str) -> bool {
+    let trimmed = line.trim();
+    if name.is_empty() || trimmed.starts_with("//") || trimmed.starts_with('*') || trimmed.starts_with("--") {
+        return false;
+    }
+    line.match_indices(name).any(|(index, _)| {
+        let before = &line[..index];
+        let after = &line[index + name.len()..];
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        if before.chars().next_back().is_some_and(is_word_char) || after.chars().next().is_some_and(is_word_char) {
+            return false;
+        }
+        let previous_word = before.split(|c: char| !is_word_char(c)).rfind(|word| !word.is_empty()).unwrap_or("");
+        if DECLARATION_KEYWORDS.contains(&previous_word) {
+            return true;
+        }
+        let after = after.trim_start();
+        if after.starts_with('=') && (after.contains("=>") || after.contains("function") || after.contains("lambda")) {
+            return true;
+        }
+        let opens_block = trimmed.ends_with('{') || trimmed.ends_with(':');
+        after.starts_with('(') && opens_block && !CONTROL_KEYWORDS.contains(&previous_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "import os\n\nclass Loader(Base):\n    def load(self, path):\n        return read(path)\n\n    def save(self):\n        pass\n\ndef read(path):\n    if check(path):\n        return open(path)\n";
+
+    #[test]
+    fn keeps_correct_ranges_and_relocates_wrong_ones() {
+        let mut yaml: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Loads files.
+functions:
+  - name: read
+    start_line: 8
+    end_line: 10
+  - name: check
+    start_line: 11
+classes:
+  - name: Loader
+    start_line: 3
+    end_line: 8
+    methods:
+      - name: load
+        start_line: 4
+        end_line: 5
+      - name: save
+        start_line: 40
+"#,
+        )
+        .unwrap();
+        verify_line_ranges(&mut yaml, SOURCE);
+
+        let read = &yaml.functions[0];
+        assert_eq!((read.start_line, read.end_line), (Some(10), Some(12)), "moved with its end");
+        let check = &yaml.functions[1];
+        assert_eq!((check.start_line, check.end_line), (None, None), "only called, never declared");
+        let loader = &yaml.classes[0];
+        assert_eq!((loader.start_line, loader.end_line), (Some(3), Some(8)));
+        assert_eq!((loader.methods[0].start_line, loader.methods[0].end_line), (Some(4), Some(5)));
+        assert_eq!((loader.methods[1].start_line, loader.methods[1].end_line), (Some(7), None));
+    }
+
+    #[test]
+    fn recognizes_declarations_across_languages() {
+        assert!(declares("pub async fn load_project(&self) -> Result<(), String> {", "load_project"));
+        assert!(declares("export const handler = async (req) => {", "handler"));
+        assert!(declares("    public void save(String path) {", "save"));
+        assert!(!declares("    if save(path) {", "save"));
+        assert!(!declares("// fn save() {", "save"));
+        assert!(!declares("fn save_all() {", "save"));
+    }
+}
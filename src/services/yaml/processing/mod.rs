@@ -1,9 +1,12 @@
 // src/services/yaml/processing/mod.rs
+pub mod chunking;
 pub mod description_parser;
 pub mod file_reader;
 pub mod gitignore_handler;
 pub mod html_generator;
+pub mod line_ranges;
 pub mod orphan_file_handler;
+pub mod rust_skeleton;
 
 use std::path::Path;
 use std::result::Result;
@@ -1,6 +1,7 @@
 // src/services/yaml/processing/orphan_file_handler.rs
 use std::path::Path;
     use crate::models::Project;
+    use crate::services::yaml::remove_from_search_indexes;
 
     pub fn handle_orphan_file(
         yaml_path: &Path,
@@ -20,6 +21,10 @@ use std::path::Path;
             );
         }
 
+        if let Some(project_dir) = yaml_path.parent() {
+            remove_from_search_indexes(project_dir, source_path);
+        }
+
         // Remove from embeddings in project settings
         if project.embeddings.remove(source_path).is_some() {
             *cleanup_needed = true;
@@ -0,0 +1,419 @@
+// src/services/yaml/processing/rust_skeleton.rs
+use crate::services::yaml::{Class, DataStructure, FileYamlData, Function, Parameter};
+use quote::ToTokens;
+use std::collections::BTreeMap;
+use syn::{Attribute, Fields, FnArg, ImplItem, Item, Pat, ReturnType, Signature, TraitItem, Type};
+
+/// Builds the structure of a Rust file with `syn`: functions with their parameters and
+/// return types, structs and enums as data structures, and impl blocks and traits as
+/// classes, each with its line range. Descriptions come from doc comments only and `calls`
+/// are left empty; the LLM fills those in, or the skeleton is used as-is when generating
+/// offline.
+pub fn rust_skeleton(source: &str) -> Result<FileYamlData, String> {
+    let file = syn::parse_file(source).map_err(|e| format!("Failed to parse Rust source: {}", e))?;
+    let mut skeleton = FileYamlData {
+        description: doc_comment(&file.attrs).unwrap_or_default(),
+        ..Default::default()
+    };
+    collect_items(&file.items, &mut skeleton);
+    Ok(skeleton)
+}
+
+/// Copies the LLM's descriptions and `calls` onto the parsed skeleton, matching items by
+/// name. Names, parameters and types stay as parsed; items the LLM invented are dropped.
+pub fn apply_annotations(skeleton: &mut FileYamlData, annotated: &FileYamlData) {
+    if !annotated.description.trim().is_empty() {
+        skeleton.description = annotated.description.clone();
+    }
+    annotate_functions(&mut skeleton.functions, &annotated.functions);
+    for class in &mut skeleton.classes {
+        let Some(annotated_class) = annotated
+            .classes
+            .iter()
+            .find(|other| other.name == class.name && other.inherits == class.inherits)
+            .or_else(|| annotated.classes.iter().find(|other| other.name == class.name))
+        else {
+            continue;
+        };
+        class.description = annotated_class.description.clone().or(class.description.take());
+        annotate_functions(&mut class.methods, &annotated_class.methods);
+    }
+    for data_structure in &mut skeleton.data_structures {
+        if let Some(annotated_ds) = annotated.data_structures.iter().find(|other| other.name == data_structure.name) {
+            data_structure.description = annotated_ds.description.clone().or(data_structure.description.take());
+        }
+    }
+}
+
+/// The part of a skeleton that starts within `start_line..=end_line`, for converting a
+/// large file chunk by chunk. A class split across chunks appears in each with the
+/// methods that fall in that chunk.
+pub fn skeleton_within(skeleton: &FileYamlData, start_line: usize, end_line: usize) -> FileYamlData {
+    let within = |line: Option<usize>| line.is_some_and(|line| line >= start_line && line <= end_line);
+    FileYamlData {
+        description: skeleton.description.clone(),
+        functions: skeleton.functions.iter().filter(|function| within(function.start_line)).cloned().collect(),
+        classes: skeleton
+            .classes
+            .iter()
+            .filter_map(|class| {
+                let methods: Vec<Function> = class.methods.iter().filter(|method| within(method.start_line)).cloned().collect();
+                (within(class.start_line) || !methods.is_empty()).then(|| Class { methods, ..class.clone() })
+            })
+            .collect(),
+        data_structures: skeleton.data_structures.iter().filter(|ds| within(ds.start_line)).cloned().collect(),
+        imports: Vec::new(),
+    }
+}
+
+fn annotate_functions(functions: &mut [Function], annotated: &[Function]) {
+    for function in functions {
+        let Some(annotated_fn) = annotated.iter().find(|other| other.name == function.name) else {
+            continue;
+        };
+        function.description = annotated_fn.description.clone().or(function.description.take());
+        function.calls = annotated_fn.calls.clone();
+        for parameter in &mut function.parameters {
+            if let Some(annotated_param) = annotated_fn.parameters.iter().find(|other| other.name == parameter.name) {
+                parameter.description = annotated_param.description.clone();
+            }
+        }
+    }
+}
+
+fn collect_items(items: &[Item], skeleton: &mut FileYamlData) {
+    for item in items {
+        match item {
+            Item::Fn(item_fn) => skeleton.functions.push(function(item_fn, &item_fn.sig, &item_fn.attrs)),
+            Item::Struct(item_struct) => skeleton.data_structures.push(DataStructure {
+                name: item_struct.ident.to_string(),
+                ds_type: "struct".to_string(),
+                description: doc_comment(&item_struct.attrs),
+                structure: fields_structure(&item_struct.fields),
+                start_line: start_line(item_struct),
+                end_line: end_line(item_struct),
+            }),
+            Item::Enum(item_enum) => skeleton.data_structures.push(DataStructure {
+                name: item_enum.ident.to_string(),
+                ds_type: "enum".to_string(),
+                description: doc_comment(&item_enum.attrs),
+                structure: item_enum
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let fields = match &variant.fields {
+                            Fields::Unit => serde_yaml::Value::Null,
+                            fields => serde_yaml::to_value(fields_structure(fields)).unwrap_or_default(),
+                        };
+                        (variant.ident.to_string(), fields)
+                    })
+                    .collect(),
+                start_line: start_line(item_enum),
+                end_line: end_line(item_enum),
+            }),
+            Item::Type(item_type) => skeleton.data_structures.push(DataStructure {
+                name: item_type.ident.to_string(),
+                ds_type: "type_alias".to_string(),
+                description: doc_comment(&item_type.attrs),
+                structure: BTreeMap::from([("type".to_string(), serde_yaml::Value::from(type_string(&item_type.ty)))]),
+                start_line: start_line(item_type),
+                end_line: end_line(item_type),
+            }),
+            Item::Trait(item_trait) => skeleton.classes.push(Class {
+                name: item_trait.ident.to_string(),
+                inherits: None,
+                description: doc_comment(&item_trait.attrs),
+                methods: item_trait
+                    .items
+                    .iter()
+                    .filter_map(|trait_item| match trait_item {
+                        TraitItem::Fn(method) => Some(function(method, &method.sig, &method.attrs)),
+                        _ => None,
+                    })
+                    .collect(),
+                properties: Vec::new(),
+                start_line: start_line(item_trait),
+                end_line: end_line(item_trait),
+            }),
+            Item::Impl(item_impl) => {
+                let methods: Vec<Function> = item_impl
+                    .items
+                    .iter()
+                    .filter_map(|impl_item| match impl_item {
+                        ImplItem::Fn(method) => Some(function(method, &method.sig, &method.attrs)),
+                        _ => None,
+                    })
+                    .collect();
+                let name = type_string(&item_impl.self_ty);
+                let inherits = item_impl.trait_.as_ref().map(|(_, path, _)| tokens_string(path));
+                // Several impls of one type read as one class, starting at the first and
+                // ending at the last so its range covers all of its methods
+                match skeleton.classes.iter_mut().find(|class| class.name == name && class.inherits == inherits) {
+                    Some(class) => {
+                        class.methods.extend(methods);
+                        class.end_line = class.end_line.max(end_line(item_impl));
+                    }
+                    None => skeleton.classes.push(Class {
+                        name,
+                        inherits,
+                        description: doc_comment(&item_impl.attrs),
+                        methods,
+                        properties: Vec::new(),
+                        start_line: start_line(item_impl),
+                        end_line: end_line(item_impl),
+                    }),
+                }
+            }
+            Item::Mod(item_mod) if !is_cfg_test(&item_mod.attrs) => {
+                if let Some((_, items)) = &item_mod.content {
+                    collect_items(items, skeleton);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn function<T: ToTokens>(item: &T, sig: &Signature, attrs: &[Attribute]) -> Function {
+    Function {
+        name: sig.ident.to_string(),
+        description: doc_comment(attrs),
+        parameters: sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                // `self` is implied by the method being listed under its class
+                FnArg::Receiver(_) => None,
+                FnArg::Typed(typed) => Some(Parameter {
+                    name: match &*typed.pat {
+                        Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+                        pat => tokens_string(pat),
+                    },
+                    param_type: type_string(&typed.ty),
+                    description: None,
+                }),
+            })
+            .collect(),
+        return_type: match &sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(type_string(ty)),
+        },
+        calls: Vec::new(),
+        start_line: start_line(item),
+        end_line: end_line(item),
+    }
+}
+
+/// First line of an item, counting its doc comments and attributes.
+fn start_line<T: ToTokens>(item: &T) -> Option<usize> {
+    let first = item.to_token_stream().into_iter().next()?;
+    Some(first.span().start().line)
+}
+
+fn end_line<T: ToTokens>(item: &T) -> Option<usize> {
+    let last = item.to_token_stream().into_iter().last()?;
+    Some(last.span().end().line)
+}
+
+/// Field name to type; tuple fields are keyed by position.
+fn fields_structure(fields: &Fields) -> BTreeMap<String, serde_yaml::Value> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let name = field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_else(|| index.to_string());
+            (name, serde_yaml::Value::from(type_string(&field.ty)))
+        })
+        .collect()
+}
+
+/// `///` and `//!` comments joined into one line, or None if there are none.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(name_value) => match &name_value.value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }) => Some(text.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+fn is_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("cfg") && tokens_string(&attr.meta).replace(' ', "") == "cfg(test)")
+}
+
+fn type_string(ty: &Type) -> String {
+    tokens_string(ty)
+}
+
+/// Token streams print with a space between every token (`Vec < & str >`); this
+/// tidies them back into the way the type would be written (`Vec<&str>`).
+fn tokens_string<T: ToTokens>(tokens: &T) -> String {
+    let mut text = tokens.to_token_stream().to_string();
+    for (from, to) in [
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ;", ";"),
+    ] {
+        text = text.replace(from, to);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+//! Loads and saves projects.
+use std::path::Path;
+
+/// Settings stored per project.
+pub struct Settings {
+    pub name: String,
+    pub tags: Vec<&'static str>,
+}
+
+pub enum Backend { Qdrant, Local(String) }
+
+pub struct ProjectService;
+
+impl ProjectService {
+    /// Reads the settings file.
+    pub fn load(&self, dir: &Path) -> Result<Settings, Box<dyn std::error::Error>> { todo!() }
+}
+
+impl Default for ProjectService {
+    fn default() -> Self { ProjectService }
+}
+
+pub async fn save(settings: &mut Settings, (a, b): (u8, u8)) {}
+
+#[cfg(test)]
+mod tests {
+    fn helper() {}
+}
+"#;
+
+    #[test]
+    fn extracts_functions_structs_and_impls() {
+        let skeleton = rust_skeleton(SOURCE).unwrap();
+        assert_eq!(skeleton.description, "Loads and saves projects.");
+
+        assert_eq!(skeleton.functions.len(), 1, "test module is skipped");
+        let save = &skeleton.functions[0];
+        assert_eq!(save.name, "save");
+        assert_eq!(save.parameters[0].name, "settings");
+        assert_eq!(save.parameters[0].param_type, "&mut Settings");
+        assert_eq!(save.parameters[1].name, "(a, b)");
+        assert_eq!(save.return_type, None);
+
+        let settings = &skeleton.data_structures[0];
+        assert_eq!(settings.ds_type, "struct");
+        assert_eq!(settings.description.as_deref(), Some("Settings stored per project."));
+        assert_eq!(settings.structure["tags"], serde_yaml::Value::from("Vec<&'static str>"));
+        let backend = &skeleton.data_structures[1];
+        assert_eq!(backend.ds_type, "enum");
+        assert!(backend.structure["Qdrant"].is_null());
+
+        let service = skeleton.classes.iter().find(|class| class.inherits.is_none()).unwrap();
+        assert_eq!(service.name, "ProjectService");
+        let load = &service.methods[0];
+        assert_eq!(load.parameters.len(), 1, "self is not a parameter");
+        assert_eq!(load.return_type.as_deref(), Some("Result<Settings, Box<dyn std::error::Error>>"));
+        assert_eq!(load.description.as_deref(), Some("Reads the settings file."));
+        let default_impl = skeleton.classes.iter().find(|class| class.inherits.is_some()).unwrap();
+        assert_eq!(default_impl.inherits.as_deref(), Some("Default"));
+
+        // SOURCE starts with an empty line
+        assert_eq!((settings.start_line, settings.end_line), (Some(5), Some(9)));
+        assert_eq!((load.start_line, load.end_line), (Some(16), Some(17)));
+        assert_eq!((save.start_line, save.end_line), (Some(24), Some(24)));
+    }
+
+    #[test]
+    fn keeps_parsed_names_when_applying_annotations() {
+        let mut skeleton = rust_skeleton(SOURCE).unwrap();
+        let annotated: FileYamlData = serde_yaml::from_str(
+            r#"
+description: Project persistence.
+functions:
+  - name: save
+    description: Writes the settings.
+    parameters:
+      - name: settings
+        type: Settings
+        description: What to write
+    calls: [write_json]
+  - name: invented_helper
+    description: Not in the source.
+classes:
+  - name: ProjectService
+    description: Loads projects.
+    methods:
+      - name: load
+        calls: [read_to_string]
+"#,
+        )
+        .unwrap();
+        apply_annotations(&mut skeleton, &annotated);
+
+        assert_eq!(skeleton.description, "Project persistence.");
+        assert_eq!(skeleton.functions.len(), 1);
+        let save = &skeleton.functions[0];
+        assert_eq!(save.description.as_deref(), Some("Writes the settings."));
+        assert_eq!(save.calls, vec!["write_json"]);
+        assert_eq!(save.parameters[0].param_type, "&mut Settings");
+        assert_eq!(save.parameters[0].description.as_deref(), Some("What to write"));
+
+        let service = skeleton.classes.iter().find(|class| class.inherits.is_none()).unwrap();
+        assert_eq!(service.description.as_deref(), Some("Loads projects."));
+        assert_eq!(service.methods[0].description.as_deref(), Some("Reads the settings file."));
+        assert_eq!(service.methods[0].calls, vec!["read_to_string"]);
+    }
+
+    #[test]
+    fn splits_skeleton_by_line_range() {
+        let skeleton = rust_skeleton(SOURCE).unwrap();
+        let part = skeleton_within(&skeleton, 14, 21);
+        assert!(part.functions.is_empty());
+        assert!(part.data_structures.is_empty());
+        assert_eq!(part.classes.len(), 2);
+        assert_eq!(part.classes[0].methods[0].name, "load");
+    }
+
+    #[test]
+    fn merged_impls_cover_all_their_methods() {
+        let skeleton = rust_skeleton("struct A;\n\nimpl A {\n    fn one(&self) {}\n}\n\nfn between() {}\n\nimpl A {\n    fn two(&self) {}\n}\n").unwrap();
+        assert_eq!(skeleton.classes.len(), 1);
+        let class = &skeleton.classes[0];
+        assert_eq!(class.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["one", "two"]);
+        assert_eq!((class.start_line, class.end_line), (Some(3), Some(11)));
+    }
+
+    #[test]
+    fn rejects_invalid_source() {
+        assert!(rust_skeleton("fn broken( {").is_err());
+    }
+}
@@ -0,0 +1,400 @@
+// src/services/yaml/symbol_index.rs
+use crate::services::yaml::keyword_index::source_path_for_yaml;
+use crate::services::yaml::{FileYamlData, Function};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const INDEX_FILE: &str = "symbol_index.json";
+/// Bumped whenever the stored shape changes; an index with another version is rebuilt.
+const INDEX_VERSION: u32 = 2;
+
+/// A function, method, class or data structure declared in a file's YAML summary.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SymbolDefinition {
+    pub name: String,
+    pub kind: String,
+    pub parent_symbol: Option<String>,
+    pub file_path: String,
+    pub description: Option<String>,
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+/// A `calls` entry: `caller` (qualified with its class for methods) calls `call` as written.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CallSite {
+    pub file_path: String,
+    pub caller: String,
+    pub call: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct FileSymbols {
+    definitions: Vec<SymbolDefinition>,
+    calls: Vec<CallSite>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchType {
+    Exact,
+    Prefix,
+    Fuzzy,
+}
+
+/// Everything known about one symbol name that matched a lookup.
+#[derive(Serialize, Debug, Clone)]
+pub struct SymbolMatch {
+    pub name: String,
+    pub match_type: MatchType,
+    pub score: f32,
+    pub definitions: Vec<SymbolDefinition>,
+    pub callers: Vec<CallSite>,
+}
+
+/// Symbols and call sites from every YAML summary in a project, keyed by source path.
+/// Persisted as `output/<project>/symbol_index.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SymbolIndex {
+    version: u32,
+    files: HashMap<String, FileSymbols>,
+}
+
+/// Loaded indexes, keyed by project output dir.
+static INDEXES: OnceLock<Mutex<HashMap<PathBuf, SymbolIndex>>> = OnceLock::new();
+
+/// Last segment of a call or qualified name with any argument list removed:
+/// `self.store.save_query_data(x)` and `QueryManager::save_query_data` both give `save_query_data`.
+fn call_target(call: &str) -> &str {
+    let without_args = call.split('(').next().unwrap_or(call).trim();
+    without_args
+        .rsplit(['.', ':'])
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(without_args)
+}
+
+/// Qualifier in front of the last segment (`GitService` in `GitService::open_repository`).
+fn qualifier(name: &str) -> Option<&str> {
+    let without_args = name.split('(').next().unwrap_or(name).trim();
+    let target = call_target(without_args);
+    let prefix = without_args[..without_args.len() - target.len()].trim_end_matches([':', '.']);
+    Some(call_target(prefix)).filter(|q| !q.is_empty())
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+impl SymbolIndex {
+    fn new() -> Self {
+        SymbolIndex {
+            version: INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    fn build(project_dir: &Path) -> Self {
+        let mut index = SymbolIndex::new();
+        if let Ok(entries) = std::fs::read_dir(project_dir) {
+            for entry in entries.filter_map(Result::ok) {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let Some(source_path) = source_path_for_yaml(&file_name) else {
+                    continue;
+                };
+                match std::fs::read_to_string(entry.path()) {
+                    Ok(content) => index.insert_yaml(&source_path, &content),
+                    Err(e) => eprintln!("Failed to read {} for symbol index: {}", file_name, e),
+                }
+            }
+        }
+        index
+    }
+
+    fn insert_yaml(&mut self, source_path: &str, yaml_content: &str) {
+        match serde_yaml::from_str::<FileYamlData>(yaml_content) {
+            Ok(yaml_data) => self.insert(source_path, &yaml_data),
+            Err(e) => {
+                eprintln!("Not indexing symbols of {}, YAML did not parse: {}", source_path, e);
+                self.files.remove(source_path);
+            }
+        }
+    }
+
+    fn insert(&mut self, source_path: &str, yaml_data: &FileYamlData) {
+        let mut symbols = FileSymbols::default();
+        let mut add_function = |function: &Function, kind: &str, parent: Option<&str>| {
+            symbols.definitions.push(SymbolDefinition {
+                name: function.name.clone(),
+                kind: kind.to_string(),
+                parent_symbol: parent.map(str::to_string),
+                file_path: source_path.to_string(),
+                description: function.description.clone(),
+                start_line: function.start_line,
+                end_line: function.end_line,
+            });
+            let caller = match parent {
+                Some(parent) => format!("{}::{}", parent, function.name),
+                None => function.name.clone(),
+            };
+            for call in &function.calls {
+                symbols.calls.push(CallSite {
+                    file_path: source_path.to_string(),
+                    caller: caller.clone(),
+                    call: call.clone(),
+                });
+            }
+        };
+
+        for function in &yaml_data.functions {
+            add_function(function, "function", None);
+        }
+        for class in &yaml_data.classes {
+            for method in &class.methods {
+                add_function(method, "method", Some(&class.name));
+            }
+        }
+        for class in &yaml_data.classes {
+            symbols.definitions.push(SymbolDefinition {
+                name: class.name.clone(),
+                kind: "class".to_string(),
+                parent_symbol: None,
+                file_path: source_path.to_string(),
+                description: class.description.clone(),
+                start_line: class.start_line,
+                end_line: class.end_line,
+            });
+        }
+        for data_structure in &yaml_data.data_structures {
+            symbols.definitions.push(SymbolDefinition {
+                name: data_structure.name.clone(),
+                kind: "data_structure".to_string(),
+                parent_symbol: None,
+                file_path: source_path.to_string(),
+                description: data_structure.description.clone(),
+                start_line: data_structure.start_line,
+                end_line: data_structure.end_line,
+            });
+        }
+
+        self.files.insert(source_path.to_string(), symbols);
+    }
+
+    /// Finds symbols named like `query` (exact, then prefix, then within a small edit
+    /// distance, all case-insensitive) with their definitions and the call sites that
+    /// reference them. A qualified query such as `GitService::open_repository` only
+    /// keeps definitions whose parent matches the qualifier, when any do.
+    pub fn lookup(&self, query: &str, limit: usize) -> Vec<SymbolMatch> {
+        let target = call_target(query).to_lowercase();
+        if target.is_empty() {
+            return Vec::new();
+        }
+        let query_qualifier = qualifier(query).map(str::to_lowercase);
+        let max_distance = (target.chars().count() / 4).max(1);
+
+        let mut matches: HashMap<String, SymbolMatch> = HashMap::new();
+        for definition in self.files.values().flat_map(|f| f.definitions.iter()) {
+            let name = definition.name.to_lowercase();
+            let (match_type, score) = if name == target {
+                (MatchType::Exact, 1.0)
+            } else if name.starts_with(&target) {
+                (MatchType::Prefix, 0.8 * target.len() as f32 / name.len() as f32)
+            } else {
+                let distance = levenshtein(&name, &target);
+                if distance > max_distance {
+                    continue;
+                }
+                (MatchType::Fuzzy, 0.5 / (1 + distance) as f32)
+            };
+
+            let entry = matches.entry(definition.name.clone()).or_insert_with(|| SymbolMatch {
+                name: definition.name.clone(),
+                match_type,
+                score,
+                definitions: Vec::new(),
+                callers: Vec::new(),
+            });
+            entry.definitions.push(definition.clone());
+        }
+
+        for symbol_match in matches.values_mut() {
+            if let Some(query_qualifier) = &query_qualifier {
+                let qualified: Vec<SymbolDefinition> = symbol_match
+                    .definitions
+                    .iter()
+                    .filter(|d| d.parent_symbol.as_deref().map(str::to_lowercase).as_ref() == Some(query_qualifier))
+                    .cloned()
+                    .collect();
+                if !qualified.is_empty() {
+                    symbol_match.definitions = qualified;
+                }
+            }
+            symbol_match.definitions.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+            let name = symbol_match.name.to_lowercase();
+            symbol_match.callers = self
+                .files
+                .values()
+                .flat_map(|f| f.calls.iter())
+                .filter(|site| call_target(&site.call).to_lowercase() == name)
+                .cloned()
+                .collect();
+            symbol_match.callers.sort_by(|a, b| a.file_path.cmp(&b.file_path).then_with(|| a.caller.cmp(&b.caller)));
+        }
+
+        let mut ranked: Vec<SymbolMatch> = matches.into_values().collect();
+        ranked.sort_by(|a, b| {
+            a.match_type
+                .cmp(&b.match_type)
+                .then_with(|| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        ranked.truncate(limit);
+        ranked
+    }
+
+    /// Source paths defining a symbol named exactly like one of `identifiers`
+    /// (case-insensitive), in the order the identifiers are given.
+    pub fn files_defining(&self, identifiers: &[&str]) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for identifier in identifiers {
+            let target = call_target(identifier).to_lowercase();
+            let mut defining: Vec<&String> = self
+                .files
+                .iter()
+                .filter(|(_, symbols)| symbols.definitions.iter().any(|d| d.name.to_lowercase() == target))
+                .map(|(path, _)| path)
+                .collect();
+            defining.sort();
+            for path in defining {
+                if !files.contains(path) {
+                    files.push(path.clone());
+                }
+            }
+        }
+        files
+    }
+}
+
+fn load_or_build(project_dir: &Path) -> SymbolIndex {
+    let stored = std::fs::read_to_string(project_dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<SymbolIndex>(&json).ok())
+        .filter(|index| index.version == INDEX_VERSION);
+    match stored {
+        Some(index) => index,
+        None => {
+            println!("Building symbol index for {}", project_dir.display());
+            let index = SymbolIndex::build(project_dir);
+            save(project_dir, &index);
+            index
+        }
+    }
+}
+
+fn save(project_dir: &Path, index: &SymbolIndex) {
+    let result = serde_json::to_string(index)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            let tmp_path = project_dir.join(format!("{}.tmp", INDEX_FILE));
+            std::fs::write(&tmp_path, json)
+                .and_then(|_| std::fs::rename(&tmp_path, project_dir.join(INDEX_FILE)))
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        eprintln!("Failed to save symbol index: {}", e);
+    }
+}
+
+/// Runs `f` on the project's index, loading (or building) it first if needed.
+fn with_index<T>(project_dir: &Path, f: impl FnOnce(&mut SymbolIndex) -> T) -> T {
+    let mut indexes = INDEXES.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    let index = indexes
+        .entry(project_dir.to_path_buf())
+        .or_insert_with(|| load_or_build(project_dir));
+    f(index)
+}
+
+pub fn update_file(project_dir: &Path, source_path: &str, yaml_content: &str) {
+    with_index(project_dir, |index| {
+        index.insert_yaml(source_path, yaml_content);
+        save(project_dir, index);
+    });
+}
+
+pub fn remove_file(project_dir: &Path, source_path: &str) {
+    with_index(project_dir, |index| {
+        index.files.remove(source_path);
+        save(project_dir, index);
+    });
+}
+
+pub fn lookup(project_dir: &Path, query: &str, limit: usize) -> Vec<SymbolMatch> {
+    with_index(project_dir, |index| index.lookup(query, limit))
+}
+
+pub fn files_defining(project_dir: &Path, identifiers: &[&str]) -> Vec<String> {
+    with_index(project_dir, |index| index.files_defining(identifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SymbolIndex {
+        let mut index = SymbolIndex::new();
+        let query_manager: FileYamlData = serde_yaml::from_str(
+            "description: Stores queries\n\
+             classes:\n  - name: QueryManager\n    methods:\n      - name: save_query_data\n        calls: [serde_json::to_string]\n",
+        )
+        .unwrap();
+        let handler: FileYamlData = serde_yaml::from_str(
+            "description: Handler\n\
+             functions:\n  - name: handle_fetch_source\n    calls: [\"self.query_manager.save_query_data(&q)\", load_project]\n",
+        )
+        .unwrap();
+        index.insert("src/query.rs", &query_manager);
+        index.insert("src/handler.rs", &handler);
+        index
+    }
+
+    #[test]
+    fn exact_lookup_returns_definition_and_callers() {
+        let matches = index().lookup("save_query_data", 10);
+        assert_eq!(matches[0].match_type, MatchType::Exact);
+        assert_eq!(matches[0].definitions[0].file_path, "src/query.rs");
+        assert_eq!(matches[0].definitions[0].parent_symbol.as_deref(), Some("QueryManager"));
+        assert_eq!(matches[0].callers.len(), 1);
+        assert_eq!(matches[0].callers[0].caller, "handle_fetch_source");
+    }
+
+    #[test]
+    fn prefix_and_fuzzy_matches() {
+        let index = index();
+        assert_eq!(index.lookup("handle_fetch", 10)[0].match_type, MatchType::Prefix);
+        let fuzzy = index.lookup("save_qeury_data", 10);
+        assert_eq!(fuzzy[0].name, "save_query_data");
+        assert_eq!(fuzzy[0].match_type, MatchType::Fuzzy);
+        assert!(index.lookup("unrelated", 10).is_empty());
+    }
+
+    #[test]
+    fn qualified_queries_and_call_targets() {
+        assert_eq!(call_target("self.store.save(x, y)"), "save");
+        assert_eq!(call_target("GitService::open_repository"), "open_repository");
+        assert_eq!(qualifier("GitService::open_repository"), Some("GitService"));
+        assert_eq!(index().lookup("QueryManager::save_query_data", 10)[0].definitions.len(), 1);
+    }
+}
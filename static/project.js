@@ -88,3 +88,126 @@ async function runClustering() {
     alert(`Error running clustering: ${error.message}`);
   }
 }
+
+async function runReconcile() {
+  const projectName = document.getElementById("project-name").value;
+  try {
+    const response = await fetch(`/api/reconcile/${projectName}`, {
+      method: "POST",
+    });
+
+    if (!response.ok) {
+      throw new Error(`HTTP error! status: ${response.status}`);
+    }
+
+    const { job_id } = await response.json();
+    // Reconciling runs as a background job; wait for it to finish
+    let job;
+    do {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      const jobResponse = await fetch(`/jobs/${job_id}`);
+      if (!jobResponse.ok) {
+        throw new Error(`HTTP error! status: ${jobResponse.status}`);
+      }
+      ({ job } = await jobResponse.json());
+    } while (job.status === "queued" || job.status === "running");
+
+    if (job.status !== "completed") {
+      throw new Error(job.error || `Reconcile job ${job.status}`);
+    }
+    const summary = job.log.map((entry) => entry.message).findLast((message) => message.includes("orphan points")) || "Done";
+    alert(`Reconcile finished: ${summary}`);
+  } catch (error) {
+    console.error("Error reconciling embeddings:", error);
+    alert(`Error reconciling embeddings: ${error.message}`);
+  }
+}
+
+// Poll the latest indexing job while it is running
+async function pollIndexingStatus() {
+  const projectName = document.getElementById("project-name")?.value;
+  const container = document.getElementById("indexing-status");
+  if (!projectName || !container) return;
+
+  try {
+    const response = await fetch(`/projects/${projectName}/indexing-status`);
+    if (!response.ok) return;
+    const { job, progress } = await response.json();
+    if (!job) return;
+
+    const failures = job.files
+      .filter((f) => f.stage === "failed")
+      .map((f) => `<li>${f.path}: ${f.error || "failed"}</li>`)
+      .join("");
+    const inFlight = job.files
+      .filter((f) => f.stage === "generating_yaml" || f.stage === "embedding")
+      .map((f) => `<li>${f.path} (${f.stage.replace("_", " ")})</li>`)
+      .join("");
+    const eta = progress.eta_seconds != null ? `, about ${progress.eta_seconds}s left` : "";
+
+    container.style.display = "block";
+    container.innerHTML = `
+      <strong>Indexing ${job.status}:</strong>
+      ${progress.done}/${progress.total - progress.skipped} files embedded,
+      ${progress.failed} failed, ${progress.skipped} up to date${eta}
+      ${inFlight ? `<ul>${inFlight}</ul>` : ""}
+      ${failures ? `<details><summary>Failures</summary><ul>${failures}</ul></details>` : ""}
+      ${job.error ? `<p>${job.error}</p>` : ""}`;
+
+    if (job.status === "running" || job.status === "queued") {
+      setTimeout(pollIndexingStatus, 2000);
+    }
+  } catch (error) {
+    console.error("Error fetching indexing status:", error);
+  }
+}
+
+document.addEventListener("DOMContentLoaded", pollIndexingStatus);
+
+async function loadJobs() {
+  const projectName = document.getElementById("project-name")?.value;
+  const container = document.getElementById("jobs-list");
+  if (!projectName || !container) return;
+
+  try {
+    const response = await fetch(`/jobs?project=${encodeURIComponent(projectName)}`);
+    if (!response.ok) return;
+    const jobs = await response.json();
+    if (jobs.length === 0) {
+      container.innerHTML = "<p>No jobs yet.</p>";
+      return;
+    }
+
+    container.innerHTML = `<ul>${jobs
+      .map((job) => {
+        const active = job.status === "queued" || job.status === "running";
+        const cancel = active && !job.cancel_requested
+          ? ` <button type="button" onclick="cancelJob('${job.id}')">Cancel</button>`
+          : "";
+        const files = job.progress.total > 0 ? ` (${job.progress.done}/${job.progress.total} files)` : "";
+        const error = job.error ? `: ${job.error}` : "";
+        return `<li>${job.kind.type.replace("_", " ")} - ${job.status}${files}${error}${cancel}</li>`;
+      })
+      .join("")}</ul>`;
+
+    if (jobs.some((job) => job.status === "queued" || job.status === "running")) {
+      setTimeout(loadJobs, 3000);
+    }
+  } catch (error) {
+    console.error("Error loading jobs:", error);
+  }
+}
+
+async function cancelJob(jobId) {
+  try {
+    const response = await fetch(`/jobs/${jobId}/cancel`, { method: "POST" });
+    if (!response.ok) {
+      alert(await response.text());
+    }
+    loadJobs();
+  } catch (error) {
+    console.error("Error cancelling job:", error);
+  }
+}
+
+document.addEventListener("DOMContentLoaded", loadJobs);